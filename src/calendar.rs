@@ -0,0 +1,153 @@
+use std::slice::Iter;
+use std::collections::HashMap;
+use village::resource::ResourceType;
+use self::Season::*;
+
+#[derive(Copy, Clone, PartialEq, Eq, Hash, Debug)]
+pub enum Season {
+    Spring,
+    Summer,
+    Autumn,
+    Winter,
+}
+
+pub const DEFAULT_SEASON_LENGTH: u32 = 30;
+
+impl Season {
+    pub fn iterator() -> Iter<'static, Season> {
+        static SEASONS: [Season; 4] = [Spring, Summer, Autumn, Winter];
+        SEASONS.iter()
+    }
+}
+
+/// Point in simulation time, as minds see it
+#[derive(Copy, Clone, PartialEq, Eq, Debug)]
+pub struct Date {
+    pub tick: u32,
+    /// Year of the simulation, starting at 1
+    pub year: u32,
+    pub season: Season,
+    /// Day of the season, starting at 1
+    pub day: u32,
+}
+
+impl Date {
+    /// Date before the first tick is simulated
+    pub fn start() -> Date {
+        Date {
+            tick: 0,
+            year: 1,
+            season: Spring,
+            day: 1,
+        }
+    }
+}
+
+/// Splits ticks into years and seasons, and sets how much each resource yields in each season
+#[derive(Clone)]
+pub struct Calendar {
+    /// Ticks in each season, in the order of `Season::iterator`
+    pub season_lengths: [u32; 4],
+    /// Share of normal production a resource yields in a season, 1.0 when not set
+    pub multipliers: HashMap<(Season, ResourceType), f64>,
+}
+
+impl Default for Calendar {
+    fn default() -> Calendar {
+        Calendar::new()
+    }
+}
+
+impl Calendar {
+    /// Calendar with seasons of equal length that do not change production
+    pub fn new() -> Calendar {
+        Calendar {
+            season_lengths: [DEFAULT_SEASON_LENGTH; 4],
+            multipliers: HashMap::new(),
+        }
+    }
+
+    /// Calendar with no harvest in winter, a larger one in summer and autumn, and less wood cut in winter
+    pub fn temperate() -> Calendar {
+        let mut calendar = Calendar::new();
+        calendar.multipliers.insert((Summer, ResourceType::Food), 1.25);
+        calendar.multipliers.insert((Autumn, ResourceType::Food), 1.5);
+        calendar.multipliers.insert((Winter, ResourceType::Food), 0.0);
+        calendar.multipliers.insert((Winter, ResourceType::Wood), 0.75);
+        calendar
+    }
+
+    pub fn year_length(&self) -> u32 {
+        self.season_lengths.iter().sum()
+    }
+
+    /// Date of a tick, the first tick simulated being tick 1
+    pub fn date(&self, tick: u32) -> Date {
+        let year_length = self.year_length();
+        if year_length == 0 {
+            return Date { tick: tick, ..Date::start() };
+        }
+
+        let elapsed = tick.saturating_sub(1);
+        let mut day_of_year = elapsed % year_length;
+        let mut season = Spring;
+        for (s, length) in Season::iterator().zip(self.season_lengths.iter()) {
+            if day_of_year < *length {
+                season = *s;
+                break;
+            }
+            day_of_year -= length;
+        }
+
+        Date {
+            tick: tick,
+            year: elapsed / year_length + 1,
+            season: season,
+            day: day_of_year + 1,
+        }
+    }
+
+    pub fn multiplier(&self, season: Season, resource_type: ResourceType) -> f64 {
+        *self.multipliers.get(&(season, resource_type)).unwrap_or(&1.0)
+    }
+
+    /// Multiplier of every resource type in a season
+    pub fn season_multipliers(&self, season: Season) -> HashMap<ResourceType, f64> {
+        ResourceType::iterator().map(|r| (*r, self.multiplier(season, *r))).collect()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use calendar::*;
+
+    #[test]
+    fn date_of_tick() {
+        let mut calendar = Calendar::new();
+        calendar.season_lengths = [2, 3, 1, 4];
+
+        assert_eq!(Date { tick: 1, year: 1, season: Spring, day: 1 }, calendar.date(1));
+        assert_eq!(Date { tick: 3, year: 1, season: Summer, day: 1 }, calendar.date(3));
+        assert_eq!(Date { tick: 6, year: 1, season: Autumn, day: 1 }, calendar.date(6));
+        assert_eq!(Date { tick: 10, year: 1, season: Winter, day: 4 }, calendar.date(10));
+        assert_eq!(Date { tick: 11, year: 2, season: Spring, day: 1 }, calendar.date(11));
+    }
+
+    #[test]
+    fn date_skips_empty_seasons() {
+        let mut calendar = Calendar::new();
+        calendar.season_lengths = [1, 0, 0, 1];
+
+        assert_eq!(Winter, calendar.date(2).season);
+        assert_eq!(Date::start(), calendar.date(0));
+    }
+
+    #[test]
+    fn temperate_multipliers() {
+        let calendar = Calendar::temperate();
+
+        assert_eq!(0.0, calendar.multiplier(Winter, ResourceType::Food));
+        assert_eq!(1.0, calendar.multiplier(Spring, ResourceType::Food));
+        assert_eq!(1.0, calendar.multiplier(Winter, ResourceType::Stone));
+    }
+}
@@ -0,0 +1,318 @@
+use simulation::*;
+use simulation::events::Event;
+use village::resource::ResourceType;
+use ratatui::{DefaultTerminal, Frame};
+use ratatui::crossterm::event::{self, KeyCode, KeyEventKind};
+use ratatui::layout::{Constraint, Layout, Rect};
+use ratatui::style::{Color, Style};
+use ratatui::symbols::Marker;
+use ratatui::text::Line;
+use ratatui::widgets::{Axis, Block, Chart, Dataset, GraphType, List, ListItem, Paragraph, Row, Table};
+use std::cell::RefCell;
+use std::collections::VecDeque;
+use std::io;
+use std::rc::Rc;
+use std::time::{Duration, Instant};
+
+/// Lines kept in the event log
+pub const EVENT_LOG_LENGTH: usize = 200;
+/// Ticks shown in the price chart
+pub const CHART_TICKS: usize = 100;
+pub const DEFAULT_TICKS_PER_SECOND: f64 = 2.0;
+pub const MAX_TICKS_PER_SECOND: f64 = 64.0;
+
+/// What the dashboard should do after a key is pressed
+#[derive(Copy, Clone, PartialEq, Eq, Debug)]
+pub enum Control {
+    Continue,
+    Quit,
+}
+
+/// Playback state of the dashboard, kept apart from the terminal so it can be driven without one
+pub struct Dashboard {
+    pub paused: bool,
+    pub ticks_per_second: f64,
+    /// Ticks to simulate regardless of pause, e.g. when stepping
+    pub pending_steps: u32,
+    events: Rc<RefCell<VecDeque<String>>>,
+}
+
+impl Default for Dashboard {
+    fn default() -> Dashboard {
+        Dashboard::new()
+    }
+}
+
+impl Dashboard {
+    pub fn new() -> Dashboard {
+        Dashboard {
+            paused: false,
+            ticks_per_second: DEFAULT_TICKS_PER_SECOND,
+            pending_steps: 0,
+            events: Rc::new(RefCell::new(VecDeque::new())),
+        }
+    }
+
+    /// Subscribes the event log to the Simulation's events
+    pub fn watch(&self, simulation: &mut Simulation) {
+        let events = self.events.clone();
+        simulation.subscribe(move |e: &Event| {
+            if let Some(line) = describe(e) {
+                let mut events = events.borrow_mut();
+                events.push_back(line);
+                if events.len() > EVENT_LOG_LENGTH {
+                    events.pop_front();
+                }
+            }
+        });
+    }
+
+    /// Space pauses and resumes, s or n steps one tick, + and - change the speed, q quits
+    pub fn handle_key(&mut self, key: KeyCode) -> Control {
+        match key {
+            KeyCode::Char(' ') => self.paused = !self.paused,
+            KeyCode::Char('s') | KeyCode::Char('n') => {
+                self.paused = true;
+                self.pending_steps += 1;
+            },
+            KeyCode::Char('+') | KeyCode::Char('=') => self.ticks_per_second = (self.ticks_per_second * 2.0).min(MAX_TICKS_PER_SECOND),
+            KeyCode::Char('-') => self.ticks_per_second = (self.ticks_per_second / 2.0).max(1.0 / MAX_TICKS_PER_SECOND),
+            KeyCode::Char('q') | KeyCode::Esc => return Control::Quit,
+            _ => {},
+        }
+        Control::Continue
+    }
+
+    /// Time between ticks at the current speed
+    pub fn tick_interval(&self) -> Duration {
+        Duration::from_secs_f64(1.0 / self.ticks_per_second)
+    }
+
+    /// Whether a tick should be simulated now, given the time since the last one
+    pub fn should_tick(&mut self, since_last_tick: Duration) -> bool {
+        if self.pending_steps > 0 {
+            self.pending_steps -= 1;
+            return true;
+        }
+        !self.paused && since_last_tick >= self.tick_interval()
+    }
+
+    pub fn event_log(&self) -> Vec<String> {
+        self.events.borrow().iter().cloned().collect()
+    }
+
+    /// Shows the Simulation in the terminal and runs it until q is pressed
+    pub fn run(&mut self, simulation: &mut Simulation) -> io::Result<()> {
+        self.watch(simulation);
+        let mut terminal = ratatui::init();
+        let result = self.run_loop(&mut terminal, simulation);
+        ratatui::restore();
+        result
+    }
+
+    fn run_loop(&mut self, terminal: &mut DefaultTerminal, simulation: &mut Simulation) -> io::Result<()> {
+        let mut last_tick = Instant::now();
+
+        loop {
+            terminal.draw(|frame| self.draw(frame, simulation))?;
+
+            let timeout = self.tick_interval().checked_sub(last_tick.elapsed()).unwrap_or(Duration::ZERO);
+            if event::poll(timeout.min(Duration::from_millis(100)))? {
+                if let event::Event::Key(key) = event::read()? {
+                    if key.kind == KeyEventKind::Press && self.handle_key(key.code) == Control::Quit {
+                        return Ok(());
+                    }
+                }
+            }
+
+            if self.should_tick(last_tick.elapsed()) {
+                simulation.simulate();
+                last_tick = Instant::now();
+            }
+        }
+    }
+
+    fn draw(&self, frame: &mut Frame, simulation: &Simulation) {
+        let [top, villages, log, status] = Layout::vertical([
+            Constraint::Percentage(40),
+            Constraint::Percentage(25),
+            Constraint::Min(5),
+            Constraint::Length(1),
+        ]).areas(frame.area());
+        let [chart, directions] = Layout::horizontal([Constraint::Min(20), Constraint::Length(18)]).areas(top);
+
+        draw_price_chart(frame, chart, simulation);
+        draw_price_directions(frame, directions, simulation);
+        draw_village_table(frame, villages, simulation);
+
+        let events = self.events.borrow();
+        let visible = log.height.saturating_sub(2) as usize;
+        let items: Vec<ListItem> = events.iter()
+            .skip(events.len().saturating_sub(visible))
+            .map(|e| ListItem::new(e.as_str()))
+            .collect();
+        frame.render_widget(List::new(items).block(Block::bordered().title("Events")), log);
+
+        let state = if self.paused { "paused" } else { "running" };
+        let date = simulation.date();
+        let line = format!(" tick {} (year {}, {:?} day {}) | {} at {} ticks/s | space pause  s step  +/- speed  q quit",
+            simulation.tick, date.year, date.season, date.day, state, self.ticks_per_second);
+        frame.render_widget(Paragraph::new(line), status);
+    }
+}
+
+fn traded_resources() -> Vec<ResourceType> {
+    ResourceType::iterator().filter(|r| **r != ResourceType::Gold).cloned().collect()
+}
+
+fn resource_color(resource_type: ResourceType) -> Color {
+    match resource_type {
+        ResourceType::Food => Color::Green,
+        ResourceType::Wood => Color::Yellow,
+        ResourceType::Stone => Color::Gray,
+        ResourceType::Fish => Color::Cyan,
+        ResourceType::Fiat => Color::LightGreen,
+        ResourceType::Gold => Color::LightYellow,
+    }
+}
+
+/// Arrow showing which way a price is moving
+pub fn direction_arrow(direction: PriceDirection) -> &'static str {
+    match direction {
+        PriceDirection::Upward => "↑",
+        PriceDirection::Downward => "↓",
+        PriceDirection::Equilibrium => "→",
+    }
+}
+
+/// Line shown in the event log for an event, None for events too frequent to be worth showing
+pub fn describe(event: &Event) -> Option<String> {
+    let line = match *event {
+        Event::TickStarted { tick } => format!("tick {} started", tick),
+        Event::RoundStarted { round, .. } => format!("trading round {}", round),
+        Event::WorkerBorn { village_id, worker_id } => format!("village {}: worker {} born", village_id, worker_id),
+        Event::WorkerDied { village_id, worker_id, cause } => format!("village {}: worker {} died ({:?})", village_id, worker_id, cause),
+        Event::WorkerReassigned { village_id, worker_id, task, resource_id } => {
+            format!("village {}: worker {} reassigned to {:?} {}", village_id, worker_id, task, resource_id)
+        },
+        Event::ResourceCollected { .. } => return None,
+        Event::TradeSubmitted { village_id, trade_type, resource_type, amount } => {
+            format!("village {}: {:?} {} {:?}", village_id, trade_type, amount, resource_type)
+        },
+        Event::TradeFilled { buyer, seller, resource_type, amount, price } => {
+            format!("village {} bought {} {:?} from village {} at {}", buyer, amount, resource_type, seller, price)
+        },
+        Event::PriceChanged { resource_type, from, to } => format!("{:?} price {} -> {}", resource_type, from, to),
+        Event::ShockStarted { village_id, kind, duration } => format!("village {}: {:?} for {} ticks", village_id, kind, duration),
+    };
+    Some(line)
+}
+
+fn draw_price_chart(frame: &mut Frame, area: Rect, simulation: &Simulation) {
+    let history = simulation.recording();
+    let start = history.len().saturating_sub(CHART_TICKS);
+    let series: Vec<(ResourceType, Vec<(f64, f64)>)> = traded_resources().into_iter()
+        .map(|r| {
+            let points = history[start..].iter()
+                .map(|record| (record.tick as f64, *record.prices.get(&r).unwrap_or(&0) as f64))
+                .collect();
+            (r, points)
+        })
+        .collect();
+
+    let first_tick = history.get(start).map(|r| r.tick as f64).unwrap_or(0.0);
+    let last_tick = (simulation.tick as f64).max(first_tick + 1.0);
+    let max_price = series.iter()
+        .flat_map(|s| s.1.iter().map(|p| p.1))
+        .fold(1.0, f64::max);
+
+    let datasets: Vec<Dataset> = series.iter()
+        .map(|&(r, ref points)| Dataset::default()
+            .name(format!("{:?}", r))
+            .marker(Marker::Braille)
+            .graph_type(GraphType::Line)
+            .style(Style::default().fg(resource_color(r)))
+            .data(points))
+        .collect();
+
+    let chart = Chart::new(datasets)
+        .block(Block::bordered().title("Prices"))
+        .x_axis(Axis::default().bounds([first_tick, last_tick]).labels(vec!(format!("{}", first_tick), format!("{}", last_tick))))
+        .y_axis(Axis::default().bounds([0.0, max_price * 1.1]).labels(vec!("0".to_string(), format!("{}", max_price))));
+    frame.render_widget(chart, area);
+}
+
+fn draw_price_directions(frame: &mut Frame, area: Rect, simulation: &Simulation) {
+    let lines: Vec<Line> = traded_resources().into_iter()
+        .map(|r| {
+            let price = *simulation.prices.get(&r).unwrap();
+            let direction = *simulation.price_directions.get(&r).unwrap();
+            Line::styled(format!("{:<6} {:>4} {}", format!("{:?}", r), price, direction_arrow(direction)), Style::default().fg(resource_color(r)))
+        })
+        .collect();
+    frame.render_widget(Paragraph::new(lines).block(Block::bordered().title("Market")), area);
+}
+
+fn draw_village_table(frame: &mut Frame, area: Rect, simulation: &Simulation) {
+    let mut header = vec!("Village".to_string(), "Workers".to_string(), "Idle".to_string());
+    header.extend(ResourceType::iterator().map(|r| format!("{:?}", r)));
+
+    let rows: Vec<Row> = simulation.village_managers().iter()
+        .map(|vm| {
+            let village = vm.village.borrow();
+            let mut cells = vec!(vm.village_id.to_string(), village.workers().len().to_string(), village.idle_worker_count().to_string());
+            cells.extend(ResourceType::iterator().map(|r| format!("{:.1}", village.stockpile.get(r).unwrap())));
+            Row::new(cells)
+        })
+        .collect();
+
+    let widths = vec!(Constraint::Length(8); header.len());
+    let table = Table::new(rows, widths)
+        .header(Row::new(header).style(Style::default().fg(Color::Cyan)))
+        .block(Block::bordered().title("Villages"));
+    frame.render_widget(table, area);
+}
+
+#[cfg(test)]
+mod tests {
+    use dashboard::*;
+
+    #[test]
+    fn pause_and_step() {
+        let mut dashboard = Dashboard::new();
+        let interval = dashboard.tick_interval();
+        assert!(dashboard.should_tick(interval));
+
+        dashboard.handle_key(KeyCode::Char(' '));
+        assert!(!dashboard.should_tick(interval));
+
+        dashboard.handle_key(KeyCode::Char('s'));
+        assert!(dashboard.should_tick(Duration::ZERO));
+        assert!(!dashboard.should_tick(interval));
+        assert!(dashboard.paused);
+    }
+
+    #[test]
+    fn speed_controls() {
+        let mut dashboard = Dashboard::new();
+
+        dashboard.handle_key(KeyCode::Char('+'));
+        assert_eq!(DEFAULT_TICKS_PER_SECOND * 2.0, dashboard.ticks_per_second);
+        for _ in 0..20 {
+            dashboard.handle_key(KeyCode::Char('+'));
+        }
+        assert_eq!(MAX_TICKS_PER_SECOND, dashboard.ticks_per_second);
+        assert_eq!(Control::Quit, dashboard.handle_key(KeyCode::Char('q')));
+    }
+
+    #[test]
+    fn event_log_from_simulation() {
+        let mut simulation = Simulation::new();
+        let dashboard = Dashboard::new();
+        dashboard.watch(&mut simulation);
+
+        simulation.simulate();
+
+        assert_eq!(vec!("tick 1 started".to_string()), dashboard.event_log());
+    }
+}
@@ -0,0 +1,56 @@
+use std::error;
+use std::fmt;
+use std::result;
+
+/// Error returned by the library, carrying the same messages as the `&'static str` errors of the engine
+/// so those can be turned into it with `?`
+#[derive(Copy, Clone, PartialEq, Eq, Debug)]
+pub struct Error {
+    message: &'static str,
+}
+
+impl Error {
+    pub fn new(message: &'static str) -> Error {
+        Error {
+            message: message,
+        }
+    }
+
+    pub fn message(&self) -> &'static str {
+        self.message
+    }
+}
+
+impl fmt::Display for Error {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        f.write_str(self.message)
+    }
+}
+
+impl error::Error for Error {}
+
+impl From<&'static str> for Error {
+    fn from(message: &'static str) -> Error {
+        Error::new(message)
+    }
+}
+
+pub type Result<T> = result::Result<T, Error>;
+
+#[cfg(test)]
+mod tests {
+    use error::*;
+
+    fn fails() -> Result<()> {
+        Err("Invalid Village ID")?;
+        Ok(())
+    }
+
+    #[test]
+    fn from_str_message() {
+        let error = fails().unwrap_err();
+
+        assert_eq!("Invalid Village ID", error.message());
+        assert_eq!("Invalid Village ID", error.to_string());
+    }
+}
@@ -0,0 +1,339 @@
+pub mod summary;
+
+use self::summary::*;
+use scenario::*;
+use simulation::*;
+use village::resource::ResourceType;
+use rng::Rng;
+use std::fs;
+use std::io;
+use std::path::Path;
+use std::sync::Mutex;
+use std::sync::atomic::{AtomicUsize, Ordering};
+use std::thread;
+
+/// Changes a Scenario to use the given value of a parameter
+pub type ApplyParameter = fn(&mut Scenario, f64);
+/// Measures an outcome of a Simulation once its run is over
+pub type Measure = fn(&Simulation) -> f64;
+
+/// Values a parameter takes in an Experiment
+#[derive(Clone, PartialEq, Debug)]
+pub enum ParameterRange {
+    Values(Vec<f64>),
+    /// Any value between the minimum and maximum; a grid sweep uses both ends
+    Uniform(f64, f64),
+}
+
+#[derive(Clone)]
+pub struct Parameter {
+    pub name: String,
+    pub apply: ApplyParameter,
+    pub range: ParameterRange,
+}
+
+impl Parameter {
+    pub fn new(name: &str, apply: ApplyParameter, range: ParameterRange) -> Parameter {
+        Parameter {
+            name: name.to_string(),
+            apply: apply,
+            range: range,
+        }
+    }
+}
+
+#[derive(Copy, Clone, PartialEq, Eq, Debug)]
+pub enum SweepMode {
+    /// Every combination of the parameters' values
+    Grid,
+    /// The given number of configurations, each value drawn at random from its range
+    Random { samples: u32, seed: u64 },
+}
+
+#[derive(Clone)]
+pub struct Metric {
+    pub name: String,
+    pub measure: Measure,
+}
+
+impl Metric {
+    pub fn new(name: &str, measure: Measure) -> Metric {
+        Metric {
+            name: name.to_string(),
+            measure: measure,
+        }
+    }
+}
+
+/// Living workers over all villages, gold in circulation and gold owed on active loans
+pub fn default_metrics() -> Vec<Metric> {
+    vec!(
+        Metric::new("population", |s| s.village_managers().iter().map(|vm| vm.village.borrow().workers().len() as f64).sum()),
+        Metric::new("money_supply", |s| s.money_supply()),
+        Metric::new("total_debt", |s| s.credit.total_debt()),
+        Metric::new("food_price", |s| *s.prices.get(&ResourceType::Food).unwrap() as f64),
+    )
+}
+
+/// Values given to each parameter, in the order of the Experiment's parameters
+pub type Configuration = Vec<f64>;
+
+/// Metrics measured at the end of one run
+pub struct RunResult {
+    /// Index of the run's configuration in `ExperimentResults::configurations`
+    pub configuration: usize,
+    pub seed: u64,
+    /// Value of each metric, in the order of the Experiment's metrics
+    pub values: Vec<f64>,
+}
+
+/// Runs a Scenario many times, over configurations of parameters and seeds
+pub struct Experiment {
+    pub scenario: Scenario,
+    pub parameters: Vec<Parameter>,
+    pub sweep_mode: SweepMode,
+    /// Each configuration is run once with each seed
+    pub seeds: Vec<u64>,
+    /// Ticks simulated in each run
+    pub ticks: u32,
+    pub metrics: Vec<Metric>,
+    /// Runs done at the same time
+    pub threads: usize,
+}
+
+impl Experiment {
+    pub fn new(scenario: Scenario, ticks: u32) -> Experiment {
+        Experiment {
+            scenario: scenario,
+            parameters: vec!(),
+            sweep_mode: SweepMode::Grid,
+            seeds: vec!(0),
+            ticks: ticks,
+            metrics: default_metrics(),
+            threads: thread::available_parallelism().map(|n| n.get()).unwrap_or(1),
+        }
+    }
+
+    /// Configurations the sweep goes over
+    /// Fails when a parameter has no values to take
+    pub fn configurations(&self) -> Result<Vec<Configuration>, &'static str> {
+        if self.parameters.iter().any(|p| p.range == ParameterRange::Values(vec!())) {
+            return Err("Empty parameter range");
+        }
+
+        let configurations = match self.sweep_mode {
+            SweepMode::Grid => {
+                let mut configurations: Vec<Configuration> = vec!(vec!());
+                for parameter in self.parameters.iter() {
+                    let values = match parameter.range {
+                        ParameterRange::Values(ref values) => values.clone(),
+                        ParameterRange::Uniform(min, max) => vec!(min, max),
+                    };
+                    configurations = configurations.iter()
+                        .flat_map(|c| values.iter().map(move |v| {
+                            let mut configuration = c.clone();
+                            configuration.push(*v);
+                            configuration
+                        }))
+                        .collect();
+                }
+                configurations
+            },
+            SweepMode::Random { samples, seed } => {
+                let mut rng = Rng::new(seed);
+                (0..samples)
+                    .map(|_| self.parameters.iter().map(|p| match p.range {
+                        ParameterRange::Values(ref values) => {
+                            let index = (rng.next_f64() * values.len() as f64) as usize;
+                            values[index.min(values.len() - 1)]
+                        },
+                        ParameterRange::Uniform(min, max) => min + rng.next_f64() * (max - min),
+                    }).collect())
+                    .collect()
+            },
+        };
+        Ok(configurations)
+    }
+
+    /// Runs every configuration with every seed, spread over the experiment's threads
+    pub fn run(&self) -> Result<ExperimentResults, &'static str> {
+        let configurations = self.configurations()?;
+        let jobs: Vec<(usize, u64)> = (0..configurations.len())
+            .flat_map(|c| self.seeds.iter().map(move |s| (c, *s)))
+            .collect();
+        let next_job = AtomicUsize::new(0);
+        let runs = Mutex::new(vec!());
+
+        thread::scope(|scope| {
+            for _ in 0..self.threads.max(1).min(jobs.len()) {
+                scope.spawn(|| {
+                    loop {
+                        let job = next_job.fetch_add(1, Ordering::SeqCst);
+                        if job >= jobs.len() {
+                            break;
+                        }
+                        let (configuration, seed) = jobs[job];
+                        let run = self.run_once(configuration, &configurations[configuration], seed);
+                        runs.lock().unwrap().push(run);
+                    }
+                });
+            }
+        });
+
+        let mut runs = runs.into_inner().unwrap();
+        runs.sort_by_key(|r| (r.configuration, r.seed));
+
+        Ok(ExperimentResults {
+            parameter_names: self.parameters.iter().map(|p| p.name.clone()).collect(),
+            metric_names: self.metrics.iter().map(|m| m.name.clone()).collect(),
+            configurations: configurations,
+            runs: runs,
+        })
+    }
+
+    fn run_once(&self, index: usize, configuration: &Configuration, seed: u64) -> RunResult {
+        let mut scenario = self.scenario.clone();
+        scenario.seed = seed;
+        for (parameter, value) in self.parameters.iter().zip(configuration.iter()) {
+            (parameter.apply)(&mut scenario, *value);
+        }
+
+        let mut simulation = scenario.build();
+        for _ in 0..self.ticks {
+            simulation.simulate();
+        }
+
+        RunResult {
+            configuration: index,
+            seed: seed,
+            values: self.metrics.iter().map(|m| (m.measure)(&simulation)).collect(),
+        }
+    }
+}
+
+pub struct ExperimentResults {
+    pub parameter_names: Vec<String>,
+    pub metric_names: Vec<String>,
+    pub configurations: Vec<Configuration>,
+    /// Every run, ordered by configuration then seed
+    pub runs: Vec<RunResult>,
+}
+
+impl ExperimentResults {
+    /// Summary of a metric over the runs of a configuration
+    pub fn summary(&self, configuration: usize, metric: usize) -> MetricSummary {
+        let values: Vec<f64> = self.runs.iter()
+            .filter(|r| r.configuration == configuration)
+            .map(|r| r.values[metric])
+            .collect();
+        MetricSummary::new(&values)
+    }
+
+    /// Summary table as CSV, one line per configuration and metric
+    pub fn summary_csv(&self) -> String {
+        let mut csv = String::new();
+        for name in self.parameter_names.iter() {
+            csv.push_str(&format!("{},", name));
+        }
+        csv.push_str("metric,runs,mean,std_dev,ci_low,ci_high\n");
+
+        for (c, configuration) in self.configurations.iter().enumerate() {
+            for (m, metric_name) in self.metric_names.iter().enumerate() {
+                let summary = self.summary(c, m);
+                for value in configuration.iter() {
+                    csv.push_str(&format!("{},", value));
+                }
+                csv.push_str(&format!("{},{},{},{},{},{}\n", metric_name, summary.runs, summary.mean, summary.std_dev, summary.ci_low, summary.ci_high));
+            }
+        }
+        csv
+    }
+
+    /// Writes the summary table from `summary_csv` to a file, replacing it if it exists
+    pub fn write_summary<P: AsRef<Path>>(&self, path: P) -> io::Result<()> {
+        fs::write(path, self.summary_csv())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use experiment::*;
+
+    fn gold_experiment() -> Experiment {
+        let mut scenario = Scenario::new(0);
+        scenario.villages.push(VillageSetup::new());
+
+        let mut experiment = Experiment::new(scenario, 2);
+        experiment.parameters.push(Parameter::new("gold", |s, v| s.villages[0].stockpile = vec!((ResourceType::Gold, v)), ParameterRange::Values(vec!(10.0, 20.0))));
+        experiment.parameters.push(Parameter::new("workers", |s, v| s.villages[0].workers = vec!(1; v as usize), ParameterRange::Uniform(0.0, 3.0)));
+        experiment.metrics = vec!(
+            Metric::new("money_supply", |s| s.money_supply()),
+            Metric::new("population", |s| s.village(1).unwrap().borrow().workers().len() as f64),
+        );
+        experiment.seeds = vec!(1, 2, 3);
+        experiment.threads = 2;
+        experiment
+    }
+
+    #[test]
+    fn grid_configurations() {
+        let experiment = gold_experiment();
+
+        assert_eq!(vec!(vec!(10.0, 0.0), vec!(10.0, 3.0), vec!(20.0, 0.0), vec!(20.0, 3.0)), experiment.configurations().unwrap());
+    }
+
+    #[test]
+    fn random_configurations() {
+        let mut experiment = gold_experiment();
+        experiment.sweep_mode = SweepMode::Random { samples: 5, seed: 7 };
+
+        let configurations = experiment.configurations().unwrap();
+
+        assert_eq!(5, configurations.len());
+        assert!(configurations.iter().all(|c| (c[0] == 10.0 || c[0] == 20.0) && c[1] >= 0.0 && c[1] < 3.0));
+        assert_eq!(configurations, experiment.configurations().unwrap());
+    }
+
+    #[test]
+    fn empty_range_rejected() {
+        let mut experiment = gold_experiment();
+        experiment.parameters[0].range = ParameterRange::Values(vec!());
+
+        assert_eq!(Err("Empty parameter range"), experiment.configurations());
+        experiment.sweep_mode = SweepMode::Random { samples: 5, seed: 7 };
+        assert_eq!(Err("Empty parameter range"), experiment.configurations());
+        assert!(experiment.run().is_err());
+    }
+
+    #[test]
+    fn run_every_configuration_and_seed() {
+        let results = gold_experiment().run().unwrap();
+
+        assert_eq!(12, results.runs.len());
+        assert_eq!((0, 1), (results.runs[0].configuration, results.runs[0].seed));
+        assert_eq!(20.0, results.summary(2, 0).mean);
+        assert_eq!(3.0, results.summary(1, 1).mean);
+        assert_eq!(0.0, results.summary(1, 1).std_dev);
+    }
+
+    #[test]
+    fn summary_csv_rows() {
+        let csv = gold_experiment().run().unwrap().summary_csv();
+        let lines: Vec<&str> = csv.lines().collect();
+
+        assert_eq!("gold,workers,metric,runs,mean,std_dev,ci_low,ci_high", lines[0]);
+        assert_eq!("10,0,money_supply,3,10,0,10,10", lines[1]);
+        assert_eq!(9, lines.len());
+    }
+
+    #[test]
+    fn write_summary_file() {
+        let results = gold_experiment().run().unwrap();
+        let path = std::env::temp_dir().join(format!("econ_sim_summary_{}.csv", std::process::id()));
+
+        results.write_summary(&path).unwrap();
+
+        assert_eq!(results.summary_csv(), fs::read_to_string(&path).unwrap());
+        fs::remove_file(&path).unwrap();
+    }
+}
@@ -0,0 +1,61 @@
+/// z value of a 95% confidence interval under the normal approximation
+pub const Z_95: f64 = 1.96;
+
+/// Mean of a metric over the runs of one configuration, with a 95% confidence interval
+#[derive(Copy, Clone, PartialEq, Debug)]
+pub struct MetricSummary {
+    pub runs: u32,
+    pub mean: f64,
+    /// Sample standard deviation, 0.0 with fewer than two runs
+    pub std_dev: f64,
+    pub ci_low: f64,
+    pub ci_high: f64,
+}
+
+impl MetricSummary {
+    pub fn new(values: &[f64]) -> MetricSummary {
+        let runs = values.len();
+        if runs == 0 {
+            return MetricSummary { runs: 0, mean: 0.0, std_dev: 0.0, ci_low: 0.0, ci_high: 0.0 };
+        }
+
+        let mean = values.iter().sum::<f64>() / runs as f64;
+        let std_dev = if runs > 1 {
+            (values.iter().map(|v| (v - mean).powi(2)).sum::<f64>() / (runs - 1) as f64).sqrt()
+        } else {
+            0.0
+        };
+        let margin = Z_95 * std_dev / (runs as f64).sqrt();
+
+        MetricSummary {
+            runs: runs as u32,
+            mean: mean,
+            std_dev: std_dev,
+            ci_low: mean - margin,
+            ci_high: mean + margin,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use experiment::summary::*;
+
+    #[test]
+    fn summary_of_values() {
+        let summary = MetricSummary::new(&[2.0, 4.0, 6.0]);
+
+        assert_eq!(3, summary.runs);
+        assert_eq!(4.0, summary.mean);
+        assert_eq!(2.0, summary.std_dev);
+        assert!((summary.ci_high - (4.0 + 1.96 * 2.0 / 3f64.sqrt())).abs() < 1e-9);
+        assert_eq!(summary.mean * 2.0, summary.ci_low + summary.ci_high);
+    }
+
+    #[test]
+    fn summary_of_one_value() {
+        let summary = MetricSummary::new(&[5.0]);
+
+        assert_eq!((5.0, 5.0, 5.0), (summary.mean, summary.ci_low, summary.ci_high));
+    }
+}
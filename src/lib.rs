@@ -0,0 +1,67 @@
+// struct literals name every field, e.g. `tick: tick`, as the rest of the crate does
+#![allow(clippy::redundant_field_names)]
+
+#[cfg(feature = "tui")]
+extern crate ratatui;
+#[cfg(feature = "server")]
+extern crate tiny_http;
+#[cfg(feature = "server")]
+extern crate tungstenite;
+#[cfg(any(feature = "server", feature = "wasm"))]
+#[macro_use]
+extern crate serde_json;
+#[cfg(feature = "python")]
+extern crate pyo3;
+#[cfg(feature = "python")]
+extern crate numpy;
+#[cfg(feature = "wasm")]
+extern crate wasm_bindgen;
+#[cfg(all(test, target_arch = "wasm32"))]
+extern crate wasm_bindgen_test;
+// the pyo3 and wasm-bindgen macros refer to ::core, which a 2015 edition crate has to bring into its root
+#[cfg(any(feature = "python", feature = "wasm"))]
+extern crate core;
+
+pub mod calendar;
+pub mod error;
+// runs on threads, which the browser doesn't have
+#[cfg(not(target_arch = "wasm32"))]
+pub mod experiment;
+// seeded random numbers for the simulation's own use, not part of the API
+pub(crate) mod rng;
+pub mod scenario;
+pub mod simulation;
+pub mod village;
+pub mod village_mind;
+#[cfg(feature = "tui")]
+pub mod dashboard;
+#[cfg(feature = "server")]
+pub mod server;
+#[cfg(feature = "python")]
+pub mod python;
+#[cfg(feature = "wasm")]
+pub mod wasm;
+
+// The stable API: build villages and a simulation, mind villages with a strategy and trade on the market
+// The modules stay public for the finer controls (e.g. credit, logistics), which may still change
+pub use error::{Error, Result};
+pub use calendar::{Calendar, Date, Season};
+pub use scenario::{Scenario, VillageSetup};
+pub use simulation::{Simulation, PriceDirection};
+pub use simulation::builder::SimulationBuilder;
+pub use simulation::barter::MarketMode;
+pub use simulation::shocks::ShockRule;
+pub use simulation::monetary_authority::{MonetaryAuthority, PolicyRule};
+pub use simulation::events::Event;
+pub use simulation::recording::{TickRecord, VillageRecord};
+pub use village::{Village, VillageId};
+pub use village::builder::VillageBuilder;
+pub use village::taxation::TaxPolicy;
+pub use village::shock::ShockKind;
+pub use village::resource::{ResourceType, ResourceId, CollectResource};
+pub use village::worker::{Worker, WorkerId};
+pub use village_mind::strategy::Strategy;
+pub use village_mind::trade_request::{TradeRequest, TradeType};
+pub use village_mind::loan_request::LoanRequest;
+pub use village_mind::barter_offer::{BarterOffer, BarterResponse};
+pub use village_mind::forward_request::ForwardRequest;
@@ -1,6 +1,77 @@
-mod simulation;
-mod village;
-mod village_mind;
+extern crate econ_sim;
+
+use econ_sim::{ResourceType, Scenario, Simulation, VillageSetup};
+use econ_sim::simulation::indicators::*;
+#[cfg(feature = "tui")]
+use econ_sim::dashboard;
+#[cfg(feature = "server")]
+use econ_sim::server;
+
+/// Two villages, one farming and one cutting wood and quarrying stone
+fn example_scenario() -> Scenario {
+    let mut farmers = VillageSetup::new();
+    farmers.workers = vec!(1; 6);
+    farmers.resources = vec!((ResourceType::Food, |x| x * 2.0));
+    farmers.stockpile = vec!((ResourceType::Food, 20.0), (ResourceType::Gold, 50.0));
+    farmers.assignments = (0..6).map(|w| (w, 0)).collect();
+
+    let mut foresters = VillageSetup::new();
+    foresters.workers = vec!(1; 6);
+    foresters.resources = vec!((ResourceType::Wood, |x| x * 2.0), (ResourceType::Stone, |x| x));
+    foresters.stockpile = vec!((ResourceType::Food, 20.0), (ResourceType::Gold, 50.0));
+    foresters.assignments = (0..6).map(|w| (w, w % 2)).collect();
+
+    let mut scenario = Scenario::new(0);
+    scenario.villages = vec!(farmers, foresters);
+    scenario
+}
+
+/// Ticks simulated when not watching the run in the dashboard
+const RUN_TICKS: u32 = 100;
 
 fn main() {
-}
\ No newline at end of file
+    let args: Vec<String> = std::env::args().collect();
+    if let Some(index) = args.iter().position(|a| a == "--server") {
+        run_server(args.get(index + 1).and_then(|p| p.parse().ok()));
+        return;
+    }
+
+    let mut simulation = example_scenario().build();
+    if args.iter().any(|a| a == "--tui") {
+        run_dashboard(&mut simulation);
+        return;
+    }
+
+    for _ in 0..RUN_TICKS {
+        simulation.simulate();
+    }
+    print!("{}", indicators_csv(simulation.recording(), DEFAULT_VOLATILITY_WINDOW));
+}
+
+#[cfg(feature = "server")]
+fn run_server(port: Option<u16>) {
+    let result = server::Server::bind(port.unwrap_or(server::DEFAULT_PORT)).and_then(|mut server| {
+        println!("listening on http://{}", server.address());
+        server.run()
+    });
+    if let Err(e) = result {
+        eprintln!("server failed: {}", e);
+    }
+}
+
+#[cfg(not(feature = "server"))]
+fn run_server(_port: Option<u16>) {
+    eprintln!("built without the server feature, rebuild with --features server");
+}
+
+#[cfg(feature = "tui")]
+fn run_dashboard(simulation: &mut Simulation) {
+    if let Err(e) = dashboard::Dashboard::new().run(simulation) {
+        eprintln!("dashboard failed: {}", e);
+    }
+}
+
+#[cfg(not(feature = "tui"))]
+fn run_dashboard(_simulation: &mut Simulation) {
+    eprintln!("built without the tui feature, rebuild with --features tui");
+}
@@ -0,0 +1,465 @@
+use simulation::*;
+use simulation::barter::MarketMode;
+use simulation::indicators::*;
+use simulation::recording::VillageRecord;
+use scenario::*;
+use village::*;
+use village::resource::*;
+use village_mind::strategy::Strategy;
+use village_mind::trade_request::*;
+use numpy::PyArray1;
+use pyo3::prelude::*;
+use pyo3::exceptions::{PyKeyError, PyValueError};
+use pyo3::types::{PyDict, PyList};
+use std::cell::RefCell;
+use std::collections::HashMap;
+use std::rc::Rc;
+
+/// Error raised by a Python strategy during a tick, kept until the tick is over
+type PendingError = Rc<RefCell<Option<PyErr>>>;
+
+fn value_error(message: &'static str) -> PyErr {
+    PyValueError::new_err(message)
+}
+
+fn parse_resource_type(name: &str) -> PyResult<ResourceType> {
+    ResourceType::iterator()
+        .find(|r| format!("{:?}", r) == name)
+        .cloned()
+        .ok_or_else(|| value_error("Invalid resource type"))
+}
+
+fn parse_trade_type(name: &str) -> PyResult<TradeType> {
+    match name {
+        "Buy" => Ok(TradeType::Buy),
+        "Sell" => Ok(TradeType::Sell),
+        _ => Err(value_error("Invalid trade type")),
+    }
+}
+
+/// Looks up an optional key of a scenario dict: a missing key gives None, any other error is raised
+fn optional_item<'py>(item: &Bound<'py, PyAny>, key: &str) -> PyResult<Option<Bound<'py, PyAny>>> {
+    match item.get_item(key) {
+        Ok(value) => Ok(Some(value)),
+        Err(e) if e.is_instance_of::<PyKeyError>(item.py()) => Ok(None),
+        Err(e) => Err(e),
+    }
+}
+
+/// Scenarios have the same shape as the server's JSON scenarios: workers are {"power": 1, "resource": 0},
+/// resource being the index of the resource they are assigned to, and resources collect one unit per unit of worker power
+fn parse_scenario(body: &Bound<PyDict>) -> PyResult<Scenario> {
+    let seed = match body.get_item("seed")? {
+        Some(seed) => seed.extract()?,
+        None => 0,
+    };
+    let mut scenario = Scenario::new(seed);
+    scenario.market_mode = match body.get_item("market_mode")? {
+        None => MarketMode::Central,
+        Some(mode) => match mode.extract::<String>()?.as_str() {
+            "Central" => MarketMode::Central,
+            "Barter" => MarketMode::Barter,
+            _ => return Err(value_error("Invalid market mode")),
+        },
+    };
+
+    let villages = body.get_item("villages")?.ok_or_else(|| value_error("Missing villages"))?;
+    for v in villages.try_iter()? {
+        let v = v?;
+        let mut setup = VillageSetup::new();
+        if let Some(resources) = optional_item(&v, "resources")? {
+            for r in resources.try_iter()? {
+                setup.resources.push((parse_resource_type(&r?.extract::<String>()?)?, |x| x));
+            }
+        }
+        if let Some(workers) = optional_item(&v, "workers")? {
+            for (index, w) in workers.try_iter()?.enumerate() {
+                let w = w?;
+                let power: u32 = w.get_item("power").and_then(|p| p.extract()).map_err(|_| value_error("Invalid worker power"))?;
+                setup.workers.push(power);
+                if let Some(resource) = optional_item(&w, "resource")? {
+                    let resource = resource.extract().map_err(|_| value_error("Invalid resource index"))?;
+                    setup.add_assignment(index, resource).map_err(value_error)?;
+                }
+            }
+        }
+        if let Some(stockpile) = optional_item(&v, "stockpile")? {
+            let stockpile: HashMap<String, f64> = stockpile.extract()?;
+            for (name, amount) in stockpile {
+                setup.add_stockpile(parse_resource_type(&name)?, amount).map_err(value_error)?;
+            }
+        }
+        scenario.villages.push(setup);
+    }
+    Ok(scenario)
+}
+
+fn goods_dict<'py>(py: Python<'py>, goods: &HashMap<ResourceType, f64>) -> PyResult<Bound<'py, PyDict>> {
+    let dict = PyDict::new(py);
+    for resource_type in ResourceType::iterator() {
+        dict.set_item(format!("{:?}", resource_type), *goods.get(resource_type).unwrap_or(&0.0))?;
+    }
+    Ok(dict)
+}
+
+fn prices_dict<'py>(py: Python<'py>, prices: &HashMap<ResourceType, u32>) -> PyResult<Bound<'py, PyDict>> {
+    let dict = PyDict::new(py);
+    for (resource_type, price) in prices.iter() {
+        dict.set_item(format!("{:?}", resource_type), *price)?;
+    }
+    Ok(dict)
+}
+
+/// State of a Village as a dict, as given to Python strategies
+fn village_dict<'py>(py: Python<'py>, village_id: VillageId, village: &Village) -> PyResult<Bound<'py, PyDict>> {
+    let workers = PyList::empty(py);
+    for worker in village.workers().iter() {
+        let w = PyDict::new(py);
+        w.set_item("worker_id", worker.worker_id)?;
+        w.set_item("assigned_resource", worker.assigned_resource)?;
+        w.set_item("task", format!("{:?}", worker.task))?;
+        w.set_item("power", worker.power)?;
+        w.set_item("age", worker.age)?;
+        w.set_item("health", worker.health)?;
+        w.set_item("morale", worker.morale)?;
+        workers.append(w)?;
+    }
+    let resources = PyList::empty(py);
+    for resource in village.resources().iter() {
+        let r = PyDict::new(py);
+        r.set_item("resource_id", resource.resource_id)?;
+        r.set_item("resource", format!("{:?}", resource.resource_type))?;
+        resources.append(r)?;
+    }
+
+    let dict = PyDict::new(py);
+    dict.set_item("village_id", village_id)?;
+    dict.set_item("workers", workers)?;
+    dict.set_item("idle_workers", village.idle_worker_count())?;
+    dict.set_item("resources", resources)?;
+    dict.set_item("stockpile", goods_dict(py, &village.stockpile)?)?;
+    Ok(dict)
+}
+
+/// Strategy implemented by a Python object
+/// trade(state, prices) returns (trade type, resource, amount) tuples, e.g. [("Buy", "Food", 5)], and is called once per
+/// clearing round until prices settle or the round limit is hit; manage_village(state) returns (worker id, resource id)
+/// assignments. Either method may be left out
+struct PyStrategy {
+    village_id: VillageId,
+    object: Py<PyAny>,
+    error: PendingError,
+}
+
+impl PyStrategy {
+    /// Calls a method of the Python object with the Village state, and the prices when given, if it has the method
+    /// Keeps the first error raised until the end of the tick
+    fn call<T, F>(&self, name: &str, village: &Village, prices: Option<&HashMap<ResourceType, u32>>, f: F) -> Option<T>
+        where F: for<'py> FnOnce(Bound<'py, PyAny>) -> PyResult<T> {
+        if self.error.borrow().is_some() {
+            return None;
+        }
+        Python::attach(|py| {
+            let result = (|| {
+                let object = self.object.bind(py);
+                if !object.hasattr(name)? {
+                    return Ok(None);
+                }
+                let state = village_dict(py, self.village_id, village)?;
+                let result = match prices {
+                    Some(prices) => object.call_method1(name, (state, prices_dict(py, prices)?))?,
+                    None => object.call_method1(name, (state,))?,
+                };
+                f(result).map(Some)
+            })();
+            result.unwrap_or_else(|e| {
+                *self.error.borrow_mut() = Some(e);
+                None
+            })
+        })
+    }
+}
+
+impl Strategy for PyStrategy {
+    fn trade(&mut self, village: &Village, prices: &HashMap<ResourceType, u32>) -> Vec<TradeRequest> {
+        self.call("trade", village, Some(prices), |result| {
+            let mut trade_requests = vec!();
+            if !result.is_none() {
+                for request in result.try_iter()? {
+                    let (trade_type, resource, amount): (String, String, u32) = request?.extract()?;
+                    trade_requests.push(TradeRequest::new(parse_trade_type(&trade_type)?, amount, parse_resource_type(&resource)?));
+                }
+            }
+            Ok(trade_requests)
+        }).unwrap_or_default()
+    }
+
+    fn manage_village(&mut self, village: &mut Village) {
+        let assignments = self.call("manage_village", village, None, |result| {
+            let mut assignments: Vec<(u32, ResourceId)> = vec!();
+            if !result.is_none() {
+                for assignment in result.try_iter()? {
+                    assignments.push(assignment?.extract()?);
+                }
+            }
+            Ok(assignments)
+        });
+
+        for (worker_id, resource_id) in assignments.unwrap_or_default() {
+            if let Err(e) = village.assign_worker(worker_id, resource_id) {
+                *self.error.borrow_mut() = Some(value_error(e));
+                return;
+            }
+        }
+    }
+}
+
+/// A Simulation driven from Python
+/// Histories are returned as dicts of NumPy columns, ready for pandas.DataFrame
+#[pyclass(unsendable, name = "Simulation")]
+pub struct PySimulation {
+    simulation: Simulation,
+    error: PendingError,
+}
+
+#[pymethods]
+impl PySimulation {
+    #[new]
+    fn new(scenario: &Bound<PyDict>) -> PyResult<PySimulation> {
+        Ok(PySimulation {
+            simulation: parse_scenario(scenario)?.build(),
+            error: Rc::new(RefCell::new(None)),
+        })
+    }
+
+    /// Simulates the given number of ticks, raising the first error of a Python strategy
+    #[pyo3(signature = (ticks = 1))]
+    fn step(&mut self, ticks: u32) -> PyResult<()> {
+        for _ in 0..ticks {
+            self.simulation.simulate();
+            if let Some(e) = self.error.borrow_mut().take() {
+                return Err(e);
+            }
+        }
+        Ok(())
+    }
+
+    #[getter]
+    fn tick(&self) -> u32 {
+        self.simulation.tick
+    }
+
+    fn village_ids(&self) -> Vec<VillageId> {
+        self.simulation.village_managers().iter().map(|vm| vm.village_id).collect()
+    }
+
+    fn prices<'py>(&self, py: Python<'py>) -> PyResult<Bound<'py, PyDict>> {
+        prices_dict(py, &self.simulation.prices)
+    }
+
+    fn village<'py>(&self, py: Python<'py>, village_id: VillageId) -> PyResult<Bound<'py, PyDict>> {
+        let village = self.simulation.village(village_id).ok_or_else(|| value_error("Invalid Village ID"))?;
+        village_dict(py, village_id, &village.borrow())
+    }
+
+    /// Submits a trade on behalf of a Village, cleared in the next tick
+    fn submit_trade(&mut self, village_id: VillageId, trade_type: &str, resource: &str, amount: u32) -> PyResult<()> {
+        let trade_request = TradeRequest::new(parse_trade_type(trade_type)?, amount, parse_resource_type(resource)?);
+        self.simulation.submit_trade(village_id, trade_request).map_err(value_error)
+    }
+
+    /// Hands a Village's decisions over to a Python object with trade and/or manage_village methods
+    fn set_strategy(&mut self, village_id: VillageId, strategy: Py<PyAny>) -> PyResult<()> {
+        let strategy = PyStrategy {
+            village_id: village_id,
+            object: strategy,
+            error: self.error.clone(),
+        };
+        self.simulation.set_strategy(village_id, Box::new(strategy)).map_err(value_error)
+    }
+
+    /// Price of each resource at the end of each tick
+    fn price_history<'py>(&self, py: Python<'py>) -> PyResult<Bound<'py, PyDict>> {
+        let recording = self.simulation.recording();
+        let columns = PyDict::new(py);
+        columns.set_item("tick", PyArray1::from_vec(py, recording.iter().map(|r| r.tick).collect()))?;
+        for resource_type in ResourceType::iterator() {
+            let prices = recording.iter().map(|r| *r.prices.get(resource_type).unwrap_or(&0)).collect();
+            columns.set_item(format!("{:?}", resource_type), PyArray1::from_vec(py, prices))?;
+        }
+        Ok(columns)
+    }
+
+    /// State of a Village at the end of each tick it existed in
+    fn village_history<'py>(&self, py: Python<'py>, village_id: VillageId) -> PyResult<Bound<'py, PyDict>> {
+        let records: Vec<(u32, &VillageRecord)> = self.simulation.recording().iter()
+            .filter_map(|r| r.village(village_id).map(|v| (r.tick, v)))
+            .collect();
+        let columns = PyDict::new(py);
+        columns.set_item("tick", PyArray1::from_vec(py, records.iter().map(|r| r.0).collect()))?;
+        columns.set_item("workers", PyArray1::from_vec(py, records.iter().map(|r| r.1.worker_count).collect()))?;
+        columns.set_item("idle_workers", PyArray1::from_vec(py, records.iter().map(|r| r.1.idle_worker_count).collect()))?;
+        columns.set_item("debt", PyArray1::from_vec(py, records.iter().map(|r| r.1.debt).collect()))?;
+        columns.set_item("credit", PyArray1::from_vec(py, records.iter().map(|r| r.1.credit).collect()))?;
+        for resource_type in ResourceType::iterator() {
+            let stockpile = records.iter().map(|r| *r.1.stockpile.get(resource_type).unwrap_or(&0.0)).collect();
+            columns.set_item(format!("stockpile_{:?}", resource_type), PyArray1::from_vec(py, stockpile))?;
+            let produced = records.iter().map(|r| *r.1.produced.get(resource_type).unwrap_or(&0.0)).collect();
+            columns.set_item(format!("produced_{:?}", resource_type), PyArray1::from_vec(py, produced))?;
+        }
+        Ok(columns)
+    }
+
+    /// Economy wide indicators for each tick
+    #[pyo3(signature = (volatility_window = DEFAULT_VOLATILITY_WINDOW))]
+    fn indicators<'py>(&self, py: Python<'py>, volatility_window: u32) -> PyResult<Bound<'py, PyDict>> {
+        let recording = self.simulation.recording();
+        let indicators: Vec<Indicators> = (0..recording.len()).map(|i| Indicators::new(recording, i, volatility_window)).collect();
+        let columns = PyDict::new(py);
+        columns.set_item("tick", PyArray1::from_vec(py, indicators.iter().map(|i| i.tick).collect()))?;
+        columns.set_item("nominal_output", PyArray1::from_vec(py, indicators.iter().map(|i| i.nominal_output).collect()))?;
+        columns.set_item("real_output", PyArray1::from_vec(py, indicators.iter().map(|i| i.real_output).collect()))?;
+        columns.set_item("price_index", PyArray1::from_vec(py, indicators.iter().map(|i| i.price_index).collect()))?;
+        columns.set_item("inflation", PyArray1::from_vec(py, indicators.iter().map(|i| i.inflation).collect()))?;
+        columns.set_item("gini_villages", PyArray1::from_vec(py, indicators.iter().map(|i| i.gini_villages).collect()))?;
+        columns.set_item("gini_workers", PyArray1::from_vec(py, indicators.iter().map(|i| i.gini_workers).collect()))?;
+        columns.set_item("trade_volume", PyArray1::from_vec(py, indicators.iter().map(|i| i.trade_volume).collect()))?;
+        columns.set_item("trade_value", PyArray1::from_vec(py, indicators.iter().map(|i| i.trade_value).collect()))?;
+        columns.set_item("unemployment", PyArray1::from_vec(py, indicators.iter().map(|i| i.unemployment).collect()))?;
+        Ok(columns)
+    }
+}
+
+#[pymodule]
+fn econ_sim(module: &Bound<PyModule>) -> PyResult<()> {
+    module.add_class::<PySimulation>()
+}
+
+#[cfg(test)]
+mod tests {
+    use python::*;
+    use simulation::events::Event;
+    use std::ffi::CString;
+
+    fn run<'py>(py: Python<'py>, code: &str) -> Bound<'py, PyDict> {
+        let locals = PyDict::new(py);
+        py.run(&CString::new(code).unwrap(), None, Some(&locals)).unwrap();
+        locals
+    }
+
+    fn default_simulation(py: Python) -> PySimulation {
+        let scenario = run(py, "scenario = {'seed': 1, 'villages': [{'resources': ['Food', 'Wood'], 'workers': [{'power': 1, 'resource': 1}, {'power': 2}], 'stockpile': {'Gold': 10.0}}]}");
+        let scenario = scenario.get_item("scenario").unwrap().unwrap();
+        PySimulation::new(scenario.cast::<PyDict>().unwrap()).unwrap()
+    }
+
+    #[test]
+    fn new_from_dict() {
+        Python::initialize();
+        Python::attach(|py| {
+            let simulation = default_simulation(py);
+            let village = simulation.simulation.village(1).unwrap().borrow();
+
+            assert_eq!(2, village.workers().len());
+            assert_eq!(village.resources()[1].resource_id, village.workers()[0].assigned_resource);
+            assert_eq!(0, village.workers()[1].assigned_resource);
+            assert_eq!(10.0, *village.stockpile.get(&ResourceType::Gold).unwrap());
+        });
+    }
+
+    #[test]
+    fn new_rejects_invalid_scenarios() {
+        Python::initialize();
+        Python::attach(|py| {
+            let scenarios = run(py, "
+scenarios = [
+    {'villages': [{'stockpile': {'Gold': -1.0}}]},
+    {'villages': [{'stockpile': {'Gold': float('nan')}}]},
+    {'villages': [{'stockpile': {'Gold': float('inf')}}]},
+    {'villages': [{'resources': ['Food'], 'workers': [{'power': 1, 'resource': 1}]}]},
+    {'villages': [['Food']]},
+]
+");
+            for scenario in scenarios.get_item("scenarios").unwrap().unwrap().try_iter().unwrap() {
+                assert!(PySimulation::new(scenario.unwrap().cast::<PyDict>().unwrap()).is_err());
+            }
+        });
+    }
+
+    #[test]
+    fn strategy_trades_and_assigns() {
+        Python::initialize();
+        Python::attach(|py| {
+            let mut simulation = default_simulation(py);
+            let strategy = run(py, "
+class Strategy:
+    def __init__(self):
+        self.done = False
+    def trade(self, state, prices):
+        if self.done:
+            return []
+        self.done = True
+        return [('Buy', 'Food', 5)]
+    def manage_village(self, state):
+        return [(w['worker_id'], state['resources'][0]['resource_id']) for w in state['workers']]
+strategy = Strategy()
+");
+            simulation.set_strategy(1, strategy.get_item("strategy").unwrap().unwrap().unbind()).unwrap();
+            simulation.step(1).unwrap();
+
+            let round = simulation.simulation.clearing_rounds().iter().find(|r| r.resource_type == ResourceType::Food).unwrap();
+            assert_eq!(5, round.buys);
+            let village = simulation.simulation.village(1).unwrap().borrow();
+            assert!(village.workers().iter().all(|w| w.assigned_resource == village.resources()[0].resource_id));
+        });
+    }
+
+    #[test]
+    fn resubmitting_strategy_ends_tick() {
+        Python::initialize();
+        Python::attach(|py| {
+            let scenario = run(py, "scenario = {'villages': [{'stockpile': {'Gold': 100.0}}, {'stockpile': {'Food': 100.0}}]}");
+            let mut simulation = PySimulation::new(scenario.get_item("scenario").unwrap().unwrap().cast::<PyDict>().unwrap()).unwrap();
+            let strategies = run(py, "
+class Buyer:
+    def trade(self, state, prices):
+        return [('Buy', 'Food', 5)]
+class Seller:
+    def trade(self, state, prices):
+        return [('Sell', 'Food', 15), ('Buy', 'Wood', 1)]
+buyer = Buyer()
+seller = Seller()
+");
+            simulation.set_strategy(1, strategies.get_item("buyer").unwrap().unwrap().unbind()).unwrap();
+            simulation.set_strategy(2, strategies.get_item("seller").unwrap().unwrap().unbind()).unwrap();
+            let bought = Rc::new(RefCell::new(0));
+            let seen = bought.clone();
+            simulation.simulation.subscribe(move |e: &Event| if let Event::TradeFilled { buyer: 1, amount, .. } = *e {
+                *seen.borrow_mut() += amount;
+            });
+            simulation.step(2).unwrap();
+
+            let rounds = simulation.simulation.clearing_rounds().iter().filter(|r| r.resource_type == ResourceType::Food).count();
+            assert_eq!(2 * simulation.simulation.max_clearing_rounds as usize, rounds);
+            assert_eq!(10, *bought.borrow());
+        });
+    }
+
+    #[test]
+    fn strategy_error_raised_by_step() {
+        Python::initialize();
+        Python::attach(|py| {
+            let mut simulation = default_simulation(py);
+            let strategy = run(py, "
+class Strategy:
+    def trade(self, state, prices):
+        return [('Steal', 'Food', 5)]
+strategy = Strategy()
+");
+            simulation.set_strategy(1, strategy.get_item("strategy").unwrap().unwrap().unbind()).unwrap();
+
+            assert!(simulation.step(1).is_err());
+            assert!(simulation.set_strategy(2, py.None()).is_err());
+        });
+    }
+}
@@ -0,0 +1,92 @@
+use simulation::Simulation;
+use simulation::barter::MarketMode;
+use scenario::*;
+use village::*;
+use village::resource::*;
+use village_mind::trade_request::*;
+use serde_json::Value;
+use std::collections::HashMap;
+use std::convert::TryFrom;
+
+pub fn parse_resource_type(value: &Value) -> Result<ResourceType, &'static str> {
+    let name = value.as_str().ok_or("Invalid resource type")?;
+    ResourceType::iterator()
+        .find(|r| format!("{:?}", r) == name)
+        .cloned()
+        .ok_or("Invalid resource type")
+}
+
+/// Workers in the scenario body are {"power": 1, "resource": 0}, resource being the index of the
+/// resource they are assigned to, and resources collect one unit per unit of worker power
+/// A worker's power must fit in a u32 and its resource index must name one of the village's resources, and stockpile
+/// amounts must be finite and not negative. Unknown resource names and market modes are rejected too
+pub fn parse_scenario(body: &Value) -> Result<Scenario, &'static str> {
+    let mut scenario = Scenario::new(body.get("seed").and_then(|s| s.as_u64()).unwrap_or(0));
+    scenario.market_mode = match body.get("market_mode").and_then(|m| m.as_str()) {
+        None | Some("Central") => MarketMode::Central,
+        Some("Barter") => MarketMode::Barter,
+        _ => return Err("Invalid market mode"),
+    };
+
+    let villages = body.get("villages").and_then(|v| v.as_array()).ok_or("Missing villages")?;
+    for v in villages {
+        let mut setup = VillageSetup::new();
+        for r in v.get("resources").and_then(|r| r.as_array()).unwrap_or(&vec!()) {
+            setup.resources.push((parse_resource_type(r)?, |x| x));
+        }
+        for (index, w) in v.get("workers").and_then(|w| w.as_array()).unwrap_or(&vec!()).iter().enumerate() {
+            let power = w.get("power").and_then(|p| p.as_u64()).ok_or("Invalid worker power")?;
+            setup.workers.push(u32::try_from(power).map_err(|_| "Invalid worker power")?);
+            if let Some(resource) = w.get("resource") {
+                let resource = resource.as_u64()
+                    .and_then(|r| usize::try_from(r).ok())
+                    .ok_or("Invalid resource index")?;
+                setup.add_assignment(index, resource)?;
+            }
+        }
+        if let Some(stockpile) = v.get("stockpile").and_then(|s| s.as_object()) {
+            for (name, amount) in stockpile {
+                let amount = amount.as_f64().ok_or("Invalid stockpile amount")?;
+                setup.add_stockpile(parse_resource_type(&Value::String(name.clone()))?, amount)?;
+            }
+        }
+        scenario.villages.push(setup);
+    }
+    Ok(scenario)
+}
+
+pub fn parse_trade_request(body: &Value) -> Result<TradeRequest, &'static str> {
+    let trade_type = match body.get("trade_type").and_then(|t| t.as_str()) {
+        Some("Buy") => TradeType::Buy,
+        Some("Sell") => TradeType::Sell,
+        _ => return Err("Invalid trade type"),
+    };
+    let resource_type = parse_resource_type(body.get("resource").unwrap_or(&Value::Null))?;
+    let amount = body.get("amount").and_then(|a| a.as_u64()).ok_or("Invalid trade amount")?;
+    let amount = u32::try_from(amount).map_err(|_| "Invalid trade amount")?;
+
+    Ok(TradeRequest::new(trade_type, amount, resource_type))
+}
+
+pub fn goods_json(goods: &HashMap<ResourceType, f64>) -> Value {
+    Value::Object(goods.iter().map(|(r, amount)| (format!("{:?}", r), json!(amount))).collect())
+}
+
+/// Price and direction of each resource
+pub fn prices_json(simulation: &Simulation) -> Value {
+    Value::Object(simulation.prices.iter()
+        .map(|(r, p)| (format!("{:?}", r), json!({
+            "price": p,
+            "direction": format!("{:?}", simulation.price_directions.get(r).unwrap()),
+        })))
+        .collect())
+}
+
+pub fn village_json(village_id: VillageId, village: &Village) -> Value {
+    json!({
+        "village_id": village_id,
+        "workers": village.workers().len(),
+        "idle_workers": village.idle_worker_count(),
+        "stockpile": goods_json(&village.stockpile),
+    })
+}
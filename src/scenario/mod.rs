@@ -0,0 +1,197 @@
+/// JSON form of scenarios and simulation state, shared by the server and the wasm bindings
+#[cfg(any(feature = "server", feature = "wasm"))]
+pub mod json;
+
+use simulation::*;
+use simulation::barter::MarketMode;
+use simulation::shocks::ShockRule;
+use village::*;
+use village::resource::*;
+use village::taxation::TaxPolicy;
+use rng::Rng;
+use calendar::Calendar;
+
+/// Starting state of one Village in a Scenario
+#[derive(Clone)]
+pub struct VillageSetup {
+    /// Power of each starting worker
+    pub workers: Vec<u32>,
+    pub resources: Vec<(ResourceType, CollectResource)>,
+    pub stockpile: Vec<(ResourceType, f64)>,
+    pub tax_policy: TaxPolicy,
+    /// Starting jobs as (index into workers, index into resources)
+    pub assignments: Vec<(usize, usize)>,
+}
+
+impl Default for VillageSetup {
+    fn default() -> VillageSetup {
+        VillageSetup::new()
+    }
+}
+
+impl VillageSetup {
+    pub fn new() -> VillageSetup {
+        VillageSetup {
+            workers: vec!(),
+            resources: vec!(),
+            stockpile: vec!(),
+            tax_policy: TaxPolicy::none(),
+            assignments: vec!(),
+        }
+    }
+
+    /// Assigns a starting worker to one of the resources added so far, rejecting an index past the last resource
+    pub fn add_assignment(&mut self, worker: usize, resource: usize) -> Result<(), &'static str> {
+        if resource >= self.resources.len() {
+            return Err("Invalid resource index");
+        }
+        self.assignments.push((worker, resource));
+        Ok(())
+    }
+
+    /// Adds a starting stockpile amount, rejecting amounts that are negative or not finite
+    pub fn add_stockpile(&mut self, resource_type: ResourceType, amount: f64) -> Result<(), &'static str> {
+        if !(amount >= 0.0 && amount.is_finite()) {
+            return Err("Invalid stockpile amount");
+        }
+        self.stockpile.push((resource_type, amount));
+        Ok(())
+    }
+
+    fn build(&self, seed: u64) -> Village {
+        let mut village = Village::with_seed(|_| false, seed);
+
+        for &(resource_type, collect_resource) in self.resources.iter() {
+            village.create_resource(resource_type, collect_resource);
+        }
+        for power in self.workers.iter() {
+            village.create_worker(*power);
+        }
+        for &(resource_type, amount) in self.stockpile.iter() {
+            *village.stockpile.get_mut(&resource_type).unwrap() = amount;
+        }
+        village.tax_policy = self.tax_policy;
+
+        let resource_ids: Vec<ResourceId> = village.resources().iter().map(|r| r.resource_id).collect();
+        let worker_ids: Vec<_> = village.workers().iter().map(|w| w.worker_id).collect();
+        for &(worker, resource) in self.assignments.iter() {
+            if let (Some(&worker_id), Some(&resource_id)) = (worker_ids.get(worker), resource_ids.get(resource)) {
+                village.assign_worker(worker_id, resource_id).unwrap();
+            }
+        }
+
+        village
+    }
+}
+
+/// Everything needed to start a Simulation, so the same setup can be run again with another seed
+#[derive(Clone)]
+pub struct Scenario {
+    pub seed: u64,
+    pub market_mode: MarketMode,
+    /// Shocks that may strike each Village every tick
+    pub shocks: Vec<ShockRule>,
+    pub calendar: Calendar,
+    pub villages: Vec<VillageSetup>,
+}
+
+impl Scenario {
+    pub fn new(seed: u64) -> Scenario {
+        Scenario {
+            seed: seed,
+            market_mode: MarketMode::Central,
+            shocks: vec!(),
+            calendar: Calendar::new(),
+            villages: vec!(),
+        }
+    }
+
+    /// Creates a Simulation with the villages of this Scenario, in order
+    /// Each Village gets its own seed derived from the Scenario seed
+    pub fn build(&self) -> Simulation {
+        let mut rng = Rng::new(self.seed);
+        let mut simulation = Simulation::with_seed(rng.next_u64());
+        simulation.market_mode = self.market_mode;
+        simulation.shock_rules = self.shocks.clone();
+        simulation.calendar = self.calendar.clone();
+
+        for setup in self.villages.iter() {
+            simulation.add_village(setup.build(rng.next_u64()));
+        }
+
+        simulation
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use scenario::*;
+    use village::taxation::*;
+    use village::shock::ShockKind;
+
+    fn default_scenario() -> Scenario {
+        let mut scenario = Scenario::new(1);
+        let mut setup = VillageSetup::new();
+        setup.workers = vec!(1, 2);
+        setup.resources = vec!((ResourceType::Wood, |x| x));
+        setup.stockpile = vec!((ResourceType::Gold, 10.0));
+        setup.tax_policy = TaxPolicy::new(0.1, 0.0, 0.0, false);
+        scenario.villages.push(setup);
+        scenario.villages.push(VillageSetup::new());
+        scenario
+    }
+
+    #[test]
+    fn build_villages() {
+        let simulation = default_scenario().build();
+
+        assert_eq!(2, simulation.village_managers().len());
+
+        let village = simulation.village(1).unwrap().borrow();
+        assert_eq!(2, village.workers().len());
+        assert_eq!(1, village.resources().len());
+        assert_eq!(10.0, *village.stockpile.get(&ResourceType::Gold).unwrap());
+        assert_eq!(0.1, village.tax_policy.production_rate);
+    }
+
+    #[test]
+    fn build_assignments() {
+        let mut scenario = default_scenario();
+        scenario.villages[0].assignments = vec!((1, 0), (2, 0));
+        let simulation = scenario.build();
+
+        let village = simulation.village(1).unwrap().borrow();
+        assert_eq!(0, village.workers()[0].assigned_resource);
+        assert_eq!(village.resources()[0].resource_id, village.workers()[1].assigned_resource);
+    }
+
+    #[test]
+    fn build_market_mode() {
+        let mut scenario = default_scenario();
+        scenario.market_mode = MarketMode::Barter;
+
+        assert_eq!(MarketMode::Barter, scenario.build().market_mode);
+    }
+
+    #[test]
+    fn build_shocks() {
+        let mut scenario = default_scenario();
+        scenario.shocks.push(ShockRule::new(ShockKind::Plague { hazard: 0.1 }, 0.01, 10));
+
+        assert_eq!(scenario.shocks, scenario.build().shock_rules);
+    }
+
+    #[test]
+    fn build_twice_same_run() {
+        let scenario = default_scenario();
+        let mut a = scenario.build();
+        let mut b = scenario.build();
+
+        for _ in 0..5 {
+            a.simulate();
+            b.simulate();
+        }
+
+        assert_eq!(a.money_supply(), b.money_supply());
+    }
+}
@@ -0,0 +1,375 @@
+use simulation::*;
+use simulation::events::Event;
+use scenario::json::*;
+use village::*;
+use serde_json::Value;
+use tiny_http::{Header, Method, Request, Response, StatusCode};
+use tungstenite::{Message, WebSocket};
+use tungstenite::handshake::derive_accept_key;
+use tungstenite::protocol::Role;
+use std::cell::RefCell;
+use std::io;
+use std::net::{Ipv4Addr, SocketAddr};
+use std::rc::Rc;
+use std::sync::mpsc;
+use std::thread::{self, JoinHandle};
+
+pub const DEFAULT_PORT: u16 = 8080;
+
+/// Most ticks one POST /step may simulate, so a single request cannot hold the server for long
+pub const MAX_STEP_TICKS: u64 = 10_000;
+
+/// Events waiting to be written to a WebSocket client, a client falling this far behind is dropped
+const SOCKET_QUEUE_LENGTH: usize = 1024;
+
+/// Error returned to the client with its HTTP status code
+type ApiError = (u16, &'static str);
+
+/// HTTP/JSON API over one Simulation, listening on localhost only
+/// Events are streamed as JSON text messages to WebSocket clients of /events
+pub struct Server {
+    http: tiny_http::Server,
+    simulation: Option<Simulation>,
+    events: Rc<RefCell<Vec<Event>>>,
+    /// Queues of the WebSocket clients, each written to from its own thread so a client that stops reading
+    /// cannot block the API
+    sockets: Vec<mpsc::SyncSender<String>>,
+}
+
+impl Server {
+    /// Listens on the given port of 127.0.0.1, port 0 picks a free port
+    pub fn bind(port: u16) -> io::Result<Server> {
+        let http = tiny_http::Server::http((Ipv4Addr::LOCALHOST, port))
+            .map_err(|e| io::Error::other(e.to_string()))?;
+
+        Ok(Server {
+            http: http,
+            simulation: None,
+            events: Rc::new(RefCell::new(vec!())),
+            sockets: vec!(),
+        })
+    }
+
+    pub fn address(&self) -> SocketAddr {
+        self.http.server_addr().to_ip().unwrap()
+    }
+
+    /// Handles requests until POST /shutdown
+    pub fn run(&mut self) -> io::Result<()> {
+        loop {
+            let request = self.http.recv()?;
+            if !self.handle(request) {
+                return Ok(());
+            }
+        }
+    }
+
+    /// Returns false once the server is asked to shut down
+    fn handle(&mut self, mut request: Request) -> bool {
+        let path: Vec<String> = request.url().split('?').next().unwrap()
+            .split('/')
+            .filter(|s| !s.is_empty())
+            .map(|s| s.to_string())
+            .collect();
+
+        if *request.method() == Method::Get && path == ["events"] {
+            self.accept_socket(request);
+            return true;
+        }
+        if *request.method() == Method::Post && path == ["shutdown"] {
+            let _ = request.respond(json_response(200, &json!({})));
+            return false;
+        }
+
+        let mut body = String::new();
+        let result = match request.as_reader().read_to_string(&mut body) {
+            Ok(_) => {
+                let body = if body.trim().is_empty() { Ok(json!({})) } else { serde_json::from_str(&body).map_err(|_| (400, "Invalid JSON")) };
+                body.and_then(|b| self.route(request.method(), &path, &b))
+            },
+            Err(_) => Err((400, "Invalid body")),
+        };
+
+        let response = match result {
+            Ok(value) => json_response(200, &value),
+            Err((status, message)) => json_response(status, &json!({ "error": message })),
+        };
+        let _ = request.respond(response);
+        self.publish_events();
+        true
+    }
+
+    fn route(&mut self, method: &Method, path: &[String], body: &Value) -> Result<Value, ApiError> {
+        let path: Vec<&str> = path.iter().map(|s| s.as_str()).collect();
+
+        match (method, path.as_slice()) {
+            (&Method::Post, ["scenario"]) => {
+                let scenario = parse_scenario(body).map_err(|e| (400, e))?;
+                let mut simulation = scenario.build();
+                let events = self.events.clone();
+                simulation.subscribe(move |e: &Event| events.borrow_mut().push(*e));
+                self.simulation = Some(simulation);
+                Ok(self.villages_json()?)
+            },
+            (&Method::Post, ["step"]) => {
+                let ticks = body.get("ticks").map(|t| t.as_u64().ok_or((400, "Invalid tick count"))).unwrap_or(Ok(1))?;
+                if ticks > MAX_STEP_TICKS {
+                    return Err((400, "Too many ticks"));
+                }
+                let simulation = self.simulation()?;
+                for _ in 0..ticks {
+                    simulation.simulate();
+                }
+                Ok(json!({ "tick": simulation.tick }))
+            },
+            (&Method::Get, ["prices"]) => {
+                Ok(prices_json(self.simulation()?))
+            },
+            (&Method::Get, ["villages"]) => self.villages_json(),
+            (&Method::Get, ["villages", id]) => {
+                let village_id = self.village_id(id)?;
+                let simulation = self.simulation()?;
+                Ok(village_json(village_id, &simulation.village(village_id).unwrap().borrow()))
+            },
+            (&Method::Get, ["villages", id, "stockpile"]) => {
+                let village_id = self.village_id(id)?;
+                let simulation = self.simulation()?;
+                let village = simulation.village(village_id).unwrap().borrow();
+                Ok(goods_json(&village.stockpile))
+            },
+            (&Method::Post, ["villages", id, "trades"]) => {
+                let village_id = self.village_id(id)?;
+                let trade_request = parse_trade_request(body).map_err(|e| (400, e))?;
+                self.simulation()?.submit_trade(village_id, trade_request).map_err(|e| (400, e))?;
+                Ok(json!({}))
+            },
+            _ => Err((404, "Not found")),
+        }
+    }
+
+    fn simulation(&mut self) -> Result<&mut Simulation, ApiError> {
+        self.simulation.as_mut().ok_or((409, "No scenario loaded"))
+    }
+
+    fn village_id(&mut self, id: &str) -> Result<VillageId, ApiError> {
+        let village_id = id.parse::<VillageId>().map_err(|_| (404, "Invalid Village ID"))?;
+        match self.simulation()?.village(village_id) {
+            Some(_) => Ok(village_id),
+            None => Err((404, "Invalid Village ID")),
+        }
+    }
+
+    fn villages_json(&mut self) -> Result<Value, ApiError> {
+        let simulation = self.simulation()?;
+        Ok(Value::Array(simulation.village_managers().iter()
+            .map(|vm| village_json(vm.village_id, &vm.village.borrow()))
+            .collect()))
+    }
+
+    fn accept_socket(&mut self, request: Request) {
+        let key = request.headers().iter()
+            .find(|h| h.field.equiv("Sec-WebSocket-Key"))
+            .map(|h| h.value.as_str().to_string());
+        let key = match key {
+            Some(key) => key,
+            None => {
+                let _ = request.respond(json_response(400, &json!({ "error": "Not a WebSocket request" })));
+                return;
+            },
+        };
+
+        let response = Response::empty(StatusCode(101))
+            .with_header(Header::from_bytes(&b"Sec-WebSocket-Accept"[..], derive_accept_key(key.as_bytes()).as_bytes()).unwrap());
+        let stream = request.upgrade("websocket", response);
+        let mut socket = WebSocket::from_raw_socket(stream, Role::Server, None);
+        let (sender, receiver) = mpsc::sync_channel::<String>(SOCKET_QUEUE_LENGTH);
+        thread::spawn(move || {
+            for message in receiver {
+                if socket.send(Message::text(message)).is_err() {
+                    break;
+                }
+            }
+        });
+        self.sockets.push(sender);
+    }
+
+    /// Queues the events published since the last call for every WebSocket client, dropping clients that left
+    /// or whose queue is full
+    fn publish_events(&mut self) {
+        let events: Vec<Event> = self.events.borrow_mut().drain(..).collect();
+        if events.is_empty() {
+            return;
+        }
+
+        let messages: Vec<String> = events.iter().map(|e| event_json(e).to_string()).collect();
+        self.sockets.retain(|socket| messages.iter().all(|m| socket.try_send(m.clone()).is_ok()));
+    }
+}
+
+/// Starts a Server on its own thread, the Simulation never leaving it
+/// Returns the address the server listens on
+pub fn spawn(port: u16) -> io::Result<(SocketAddr, JoinHandle<io::Result<()>>)> {
+    let (sender, receiver) = mpsc::channel();
+    let handle = thread::spawn(move || {
+        let mut server = match Server::bind(port) {
+            Ok(server) => server,
+            Err(e) => {
+                let _ = sender.send(Err(e));
+                return Ok(());
+            },
+        };
+        let _ = sender.send(Ok(server.address()));
+        server.run()
+    });
+
+    let address = receiver.recv().map_err(|e| io::Error::other(e.to_string()))??;
+    Ok((address, handle))
+}
+
+fn json_response(status: u16, value: &Value) -> Response<io::Cursor<Vec<u8>>> {
+    Response::from_string(value.to_string())
+        .with_status_code(status)
+        .with_header(Header::from_bytes(&b"Content-Type"[..], &b"application/json"[..]).unwrap())
+}
+
+/// Event as a JSON object, its kind under "event"
+pub fn event_json(event: &Event) -> Value {
+    match *event {
+        Event::TickStarted { tick } => json!({ "event": "TickStarted", "tick": tick }),
+        Event::RoundStarted { tick, round } => json!({ "event": "RoundStarted", "tick": tick, "round": round }),
+        Event::WorkerBorn { village_id, worker_id } => json!({ "event": "WorkerBorn", "village_id": village_id, "worker_id": worker_id }),
+        Event::WorkerDied { village_id, worker_id, cause } => json!({
+            "event": "WorkerDied", "village_id": village_id, "worker_id": worker_id, "cause": format!("{:?}", cause),
+        }),
+        Event::WorkerReassigned { village_id, worker_id, task, resource_id } => json!({
+            "event": "WorkerReassigned", "village_id": village_id, "worker_id": worker_id, "task": format!("{:?}", task), "resource_id": resource_id,
+        }),
+        Event::ResourceCollected { village_id, resource_id, resource_type, amount } => json!({
+            "event": "ResourceCollected", "village_id": village_id, "resource_id": resource_id, "resource": format!("{:?}", resource_type), "amount": amount,
+        }),
+        Event::TradeSubmitted { village_id, trade_type, resource_type, amount } => json!({
+            "event": "TradeSubmitted", "village_id": village_id, "trade_type": format!("{:?}", trade_type), "resource": format!("{:?}", resource_type), "amount": amount,
+        }),
+        Event::TradeFilled { buyer, seller, resource_type, amount, price } => json!({
+            "event": "TradeFilled", "buyer": buyer, "seller": seller, "resource": format!("{:?}", resource_type), "amount": amount, "price": price,
+        }),
+        Event::PriceChanged { resource_type, from, to } => json!({
+            "event": "PriceChanged", "resource": format!("{:?}", resource_type), "from": from, "to": to,
+        }),
+        Event::ShockStarted { village_id, kind, duration } => json!({
+            "event": "ShockStarted", "village_id": village_id, "kind": format!("{:?}", kind), "duration": duration,
+        }),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use server::*;
+    use simulation::price_formation::DEFAULT_MAX_CLEARING_ROUNDS;
+    use std::io::{Read, Write};
+    use std::net::TcpStream;
+
+    /// Minimal HTTP client, returns the status code and the JSON body
+    fn request(address: SocketAddr, method: &str, path: &str, body: &str) -> (u16, Value) {
+        let mut stream = TcpStream::connect(address).unwrap();
+        write!(stream, "{} {} HTTP/1.1\r\nHost: localhost\r\nContent-Length: {}\r\nConnection: close\r\n\r\n{}", method, path, body.len(), body).unwrap();
+        let mut response = String::new();
+        stream.read_to_string(&mut response).unwrap();
+
+        let status = response.split(' ').nth(1).unwrap().parse().unwrap();
+        let body = response.split("\r\n\r\n").nth(1).unwrap();
+        (status, serde_json::from_str(body).unwrap())
+    }
+
+    const SCENARIO: &str = r#"{"seed": 1, "villages": [
+        {"resources": ["Food"], "workers": [{"power": 2, "resource": 0}, {"power": 1}], "stockpile": {"Gold": 30}},
+        {"resources": [], "workers": []}
+    ]}"#;
+
+    #[test]
+    fn drive_simulation() {
+        let (address, handle) = spawn(0).unwrap();
+        assert!(address.ip().is_loopback());
+
+        assert_eq!(409, request(address, "GET", "/prices", "").0);
+
+        let (status, villages) = request(address, "POST", "/scenario", SCENARIO);
+        assert_eq!(200, status);
+        assert_eq!(2, villages.as_array().unwrap().len());
+        assert_eq!(json!(1), villages[0]["idle_workers"]);
+
+        assert_eq!(400, request(address, "POST", "/villages/1/trades", r#"{"trade_type": "Buy", "resource": "Gold", "amount": 5}"#).0);
+        assert_eq!(200, request(address, "POST", "/villages/1/trades", r#"{"trade_type": "Buy", "resource": "Food", "amount": 5}"#).0);
+
+        let (_, step) = request(address, "POST", "/step", r#"{"ticks": 2}"#);
+        assert_eq!(json!(2), step["tick"]);
+
+        let (_, prices) = request(address, "GET", "/prices", "");
+        // nobody sells food, so the submitted buy raises its price in every round up to the round limit
+        assert_eq!(json!(5 + DEFAULT_MAX_CLEARING_ROUNDS), prices["Food"]["price"]);
+
+        let (_, stockpile) = request(address, "GET", "/villages/1/stockpile", "");
+        assert!(stockpile["Food"].as_f64().unwrap() > 0.0);
+        assert_eq!(json!(30.0), stockpile["Gold"]);
+        assert_eq!(404, request(address, "GET", "/villages/3", "").0);
+
+        request(address, "POST", "/shutdown", "");
+        handle.join().unwrap().unwrap();
+    }
+
+    #[test]
+    fn rejects_invalid_input() {
+        let (address, handle) = spawn(0).unwrap();
+
+        let invalid_scenarios = [
+            (r#"{"villages": [{"workers": [{"power": 4294967301}]}]}"#, "Invalid worker power"),
+            (r#"{"villages": [{"resources": ["Food"], "workers": [{"power": 1, "resource": 1}]}]}"#, "Invalid resource index"),
+            (r#"{"villages": [{"stockpile": {"Gold": -5}}]}"#, "Invalid stockpile amount"),
+        ];
+        for &(scenario, error) in invalid_scenarios.iter() {
+            let (status, body) = request(address, "POST", "/scenario", scenario);
+            assert_eq!((400, json!(error)), (status, body["error"].clone()));
+        }
+
+        request(address, "POST", "/scenario", SCENARIO);
+        let (status, body) = request(address, "POST", "/villages/1/trades", r#"{"trade_type": "Buy", "resource": "Food", "amount": 4294967301}"#);
+        assert_eq!((400, json!("Invalid trade amount")), (status, body["error"].clone()));
+        let (status, body) = request(address, "POST", "/step", r#"{"ticks": 1000000000000000}"#);
+        assert_eq!((400, json!("Too many ticks")), (status, body["error"].clone()));
+        assert_eq!(json!(0), request(address, "POST", "/step", r#"{"ticks": 0}"#).1["tick"]);
+
+        request(address, "POST", "/shutdown", "");
+        handle.join().unwrap().unwrap();
+    }
+
+    #[test]
+    fn barter_mode_rejects_trades() {
+        let (address, handle) = spawn(0).unwrap();
+        let scenario = SCENARIO.replacen("{\"seed\": 1,", "{\"seed\": 1, \"market_mode\": \"Barter\",", 1);
+        assert_eq!(200, request(address, "POST", "/scenario", &scenario).0);
+
+        let (status, body) = request(address, "POST", "/villages/1/trades", r#"{"trade_type": "Buy", "resource": "Food", "amount": 5}"#);
+        assert_eq!(400, status);
+        assert_eq!(json!("Trades are not cleared in barter mode"), body["error"]);
+
+        request(address, "POST", "/shutdown", "");
+        handle.join().unwrap().unwrap();
+    }
+
+    #[test]
+    fn stream_events() {
+        let (address, handle) = spawn(0).unwrap();
+        request(address, "POST", "/scenario", SCENARIO);
+
+        let stream = TcpStream::connect(address).unwrap();
+        let (mut socket, _) = tungstenite::client(format!("ws://{}/events", address), stream).unwrap();
+        request(address, "POST", "/step", "");
+
+        let message = socket.read().unwrap();
+        let event: Value = serde_json::from_str(message.to_text().unwrap()).unwrap();
+        assert_eq!(json!({ "event": "TickStarted", "tick": 1 }), event);
+
+        request(address, "POST", "/shutdown", "");
+        handle.join().unwrap().unwrap();
+    }
+}
@@ -0,0 +1,121 @@
+use village_mind::barter_offer::*;
+
+pub const DEFAULT_MAX_BARTER_ROUNDS: u32 = 5;
+
+/// How villages trade with each other
+#[derive(Copy, Clone, PartialEq, Eq, Hash, Debug)]
+pub enum MarketMode {
+    /// Trade requests cleared at a single price per resource by `Simulation::handle_trades`
+    Central,
+    /// Bilateral barter offers negotiated directly between village minds
+    Barter,
+}
+
+#[derive(Copy, Clone, PartialEq, Eq, Hash, Debug)]
+pub enum NegotiationOutcome {
+    Accepted,
+    Rejected,
+    /// Still countering when the maximum number of rounds was reached
+    Expired,
+    /// Not allowed by the villages' trade policies
+    Blocked,
+    /// Accepted, but one of the villages could not deliver its goods
+    Failed,
+}
+
+/// Every offer made between two villages for one exchange and how it ended
+pub struct Negotiation {
+    pub tick: u32,
+    /// Offers in the order they were made, the last one holds the final terms
+    pub offers: Vec<BarterOffer>,
+    pub outcome: NegotiationOutcome,
+}
+
+impl Negotiation {
+    pub fn final_offer(&self) -> &BarterOffer {
+        self.offers.last().unwrap()
+    }
+}
+
+/// Passes an offer back and forth between the two villages until it is accepted, rejected
+/// or the maximum number of rounds is reached
+/// `respond` is asked for the answer of the Village each offer is made to
+pub fn negotiate<F>(tick: u32, offer: BarterOffer, max_rounds: u32, mut respond: F) -> Negotiation
+    where F: FnMut(&BarterOffer) -> BarterResponse {
+    let mut offers = vec!(offer);
+
+    loop {
+        let response = respond(offers.last().unwrap());
+        let outcome = match response {
+            BarterResponse::Accept => NegotiationOutcome::Accepted,
+            BarterResponse::Reject => NegotiationOutcome::Rejected,
+            BarterResponse::Counter { give, take } => {
+                let counter = offers.last().unwrap().counter(give, take);
+                if counter.round > max_rounds {
+                    NegotiationOutcome::Expired
+                } else {
+                    offers.push(counter);
+                    continue;
+                }
+            },
+        };
+
+        return Negotiation {
+            tick: tick,
+            offers: offers,
+            outcome: outcome,
+        };
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use simulation::barter::*;
+    use village::resource::ResourceType;
+
+    fn offer() -> BarterOffer {
+        let mut offer = BarterOffer::new(2, (ResourceType::Wood, 10), (ResourceType::Food, 3));
+        offer.from = 1;
+        offer
+    }
+
+    #[test]
+    fn negotiate_accept() {
+        let negotiation = negotiate(1, offer(), 5, |_| BarterResponse::Accept);
+
+        assert_eq!(NegotiationOutcome::Accepted, negotiation.outcome);
+        assert_eq!(1, negotiation.offers.len());
+    }
+
+    #[test]
+    fn negotiate_counter_then_accept() {
+        let negotiation = negotiate(1, offer(), 5, |o| {
+            if o.from == 1 {
+                BarterResponse::Counter { give: (ResourceType::Food, 2), take: (ResourceType::Wood, 10) }
+            } else {
+                BarterResponse::Accept
+            }
+        });
+
+        assert_eq!(NegotiationOutcome::Accepted, negotiation.outcome);
+        assert_eq!(2, negotiation.final_offer().from);
+        assert_eq!(1, negotiation.final_offer().to);
+        assert_eq!((ResourceType::Food, 2), negotiation.final_offer().give);
+        assert_eq!(2, negotiation.final_offer().round);
+    }
+
+    #[test]
+    fn negotiate_expires() {
+        let negotiation = negotiate(1, offer(), 3, |o| BarterResponse::Counter { give: o.take, take: o.give });
+
+        assert_eq!(NegotiationOutcome::Expired, negotiation.outcome);
+        assert_eq!(3, negotiation.offers.len());
+    }
+
+    #[test]
+    fn negotiate_reject() {
+        let negotiation = negotiate(1, offer(), 5, |_| BarterResponse::Reject);
+
+        assert_eq!(NegotiationOutcome::Rejected, negotiation.outcome);
+    }
+}
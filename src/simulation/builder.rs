@@ -0,0 +1,146 @@
+use simulation::*;
+use simulation::monetary_authority::MonetaryAuthority;
+use error::*;
+
+/// Builds a Simulation with its villages, market and the strategies minding each Village
+/// Villages get ids in the order they are added, starting at 1
+pub struct SimulationBuilder {
+    seed: u64,
+    market_mode: MarketMode,
+    max_barter_rounds: u32,
+    max_clearing_rounds: u32,
+    calendar: Calendar,
+    shock_rules: Vec<ShockRule>,
+    monetary_authority: Option<MonetaryAuthority>,
+    villages: Vec<(Village, Option<Box<dyn Strategy>>)>,
+}
+
+impl Default for SimulationBuilder {
+    fn default() -> SimulationBuilder {
+        SimulationBuilder::new()
+    }
+}
+
+impl SimulationBuilder {
+    pub fn new() -> SimulationBuilder {
+        SimulationBuilder {
+            seed: 0,
+            market_mode: MarketMode::Central,
+            max_barter_rounds: DEFAULT_MAX_BARTER_ROUNDS,
+            max_clearing_rounds: DEFAULT_MAX_CLEARING_ROUNDS,
+            calendar: Calendar::new(),
+            shock_rules: vec!(),
+            monetary_authority: None,
+            villages: vec!(),
+        }
+    }
+
+    /// Seed driving the Simulation's random events, e.g. lost caravans and shocks
+    pub fn seed(mut self, seed: u64) -> SimulationBuilder {
+        self.seed = seed;
+        self
+    }
+
+    pub fn market_mode(mut self, market_mode: MarketMode) -> SimulationBuilder {
+        self.market_mode = market_mode;
+        self
+    }
+
+    pub fn max_barter_rounds(mut self, max_barter_rounds: u32) -> SimulationBuilder {
+        self.max_barter_rounds = max_barter_rounds;
+        self
+    }
+
+    pub fn max_clearing_rounds(mut self, max_clearing_rounds: u32) -> SimulationBuilder {
+        self.max_clearing_rounds = max_clearing_rounds;
+        self
+    }
+
+    pub fn calendar(mut self, calendar: Calendar) -> SimulationBuilder {
+        self.calendar = calendar;
+        self
+    }
+
+    pub fn shock(mut self, shock_rule: ShockRule) -> SimulationBuilder {
+        self.shock_rules.push(shock_rule);
+        self
+    }
+
+    pub fn monetary_authority(mut self, monetary_authority: MonetaryAuthority) -> SimulationBuilder {
+        self.monetary_authority = Some(monetary_authority);
+        self
+    }
+
+    /// Adds a Village left to the default mind, which makes no decisions
+    pub fn village(mut self, village: Village) -> SimulationBuilder {
+        self.villages.push((village, None));
+        self
+    }
+
+    /// Adds a Village whose decisions are made by the given strategy
+    pub fn village_with_strategy(mut self, village: Village, strategy: Box<dyn Strategy>) -> SimulationBuilder {
+        self.villages.push((village, Some(strategy)));
+        self
+    }
+
+    pub fn build(self) -> Result<Simulation> {
+        if self.max_barter_rounds == 0 {
+            return Err(Error::new("Invalid barter round limit"));
+        }
+        if self.max_clearing_rounds == 0 {
+            return Err(Error::new("Invalid clearing round limit"));
+        }
+        if self.shock_rules.iter().any(|r| r.probability < 0.0 || r.probability > 1.0) {
+            return Err(Error::new("Invalid shock probability"));
+        }
+
+        let mut simulation = Simulation::with_seed(self.seed);
+        simulation.market_mode = self.market_mode;
+        simulation.max_barter_rounds = self.max_barter_rounds;
+        simulation.max_clearing_rounds = self.max_clearing_rounds;
+        simulation.calendar = self.calendar;
+        simulation.shock_rules = self.shock_rules;
+        simulation.monetary_authority = self.monetary_authority;
+
+        for (village, strategy) in self.villages {
+            let village_id = simulation.add_village(village);
+            if let Some(strategy) = strategy {
+                simulation.set_strategy(village_id, strategy)?;
+            }
+        }
+
+        Ok(simulation)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use simulation::builder::*;
+    use village::shock::ShockKind;
+
+    struct Idle;
+
+    impl Strategy for Idle {}
+
+    #[test]
+    fn build_simulation() {
+        let simulation = SimulationBuilder::new()
+            .seed(3)
+            .market_mode(MarketMode::Barter)
+            .village(Village::new(|_| false))
+            .village_with_strategy(Village::new(|_| false), Box::new(Idle))
+            .build()
+            .unwrap();
+
+        assert_eq!(MarketMode::Barter, simulation.market_mode);
+        assert_eq!(2, simulation.village_managers().len());
+        assert!(simulation.village(2).is_some());
+    }
+
+    #[test]
+    fn build_invalid_simulation() {
+        assert!(SimulationBuilder::new().max_barter_rounds(0).build().is_err());
+        assert!(SimulationBuilder::new().max_clearing_rounds(0).build().is_err());
+        assert!(SimulationBuilder::new().shock(ShockRule::new(ShockKind::MineCollapse, 2.0, 1)).build().is_err());
+    }
+}
@@ -0,0 +1,162 @@
+use village::VillageId;
+use village::resource::ResourceType;
+
+pub type LoanId = u32;
+
+pub const DEFAULT_CENTRAL_RATE: f64 = 0.01;
+pub const DEFAULT_MAX_MISSED_PAYMENTS: u32 = 3;
+
+#[derive(Copy, Clone, PartialEq, Debug)]
+pub enum Lender {
+    Village(VillageId),
+    /// Lender of last resort outside of any village, it creates the gold it lends
+    Central,
+}
+
+#[derive(Copy, Clone, PartialEq, Eq, Hash, Debug)]
+pub enum LoanStatus {
+    Active,
+    Repaid,
+    Defaulted,
+}
+
+pub struct Loan {
+    pub loan_id: LoanId,
+    pub lender: Lender,
+    pub borrower: VillageId,
+    pub principal: f64,
+    /// Interest charged on the balance each tick
+    pub interest_rate: f64,
+    /// Fixed payment due each tick that repays the loan over its term
+    pub installment: f64,
+    /// Gold still owed
+    pub balance: f64,
+    /// Goods the lender seizes from the borrower's stockpile on default
+    pub collateral: (ResourceType, f64),
+    pub missed_payments: u32,
+    pub status: LoanStatus,
+}
+
+/// Fixed payment per tick that repays the principal with interest over the term
+pub fn installment(principal: f64, interest_rate: f64, term: u32) -> f64 {
+    let term = term.max(1) as f64;
+    if interest_rate <= 0.0 {
+        principal / term
+    } else {
+        principal * interest_rate / (1.0 - (1.0 + interest_rate).powf(-term))
+    }
+}
+
+pub struct CreditMarket {
+    /// Interest per tick charged by the central lender
+    pub central_rate: f64,
+    /// Payments a borrower may miss in a row before the loan defaults
+    pub max_missed_payments: u32,
+
+    loans: Vec<Loan>,
+    loan_id_counter: u32,
+}
+
+impl CreditMarket {
+    pub fn new(central_rate: f64, max_missed_payments: u32) -> CreditMarket {
+        CreditMarket {
+            central_rate: central_rate,
+            max_missed_payments: max_missed_payments,
+            loans: vec!(),
+            loan_id_counter: 0,
+        }
+    }
+
+    /// Records a new loan, the caller is responsible for moving the principal to the borrower
+    /// Loan id values start at 1 and auto increment in subsequent invocations
+    /// Returns the id of the Loan
+    pub fn open(&mut self, lender: Lender, borrower: VillageId, principal: f64, interest_rate: f64, term: u32, collateral: (ResourceType, f64)) -> LoanId {
+        self.loan_id_counter += 1;
+        self.loans.push(Loan {
+            loan_id: self.loan_id_counter,
+            lender: lender,
+            borrower: borrower,
+            principal: principal,
+            interest_rate: interest_rate,
+            installment: installment(principal, interest_rate, term),
+            balance: principal,
+            collateral: collateral,
+            missed_payments: 0,
+            status: LoanStatus::Active,
+        });
+
+        self.loan_id_counter
+    }
+
+    pub fn loan(&self, loan_id: LoanId) -> Option<&Loan> {
+        self.loans.iter().find(|l| l.loan_id == loan_id)
+    }
+
+    pub fn loans(&self) -> &Vec<Loan> {
+        &self.loans
+    }
+
+    pub fn loans_mut(&mut self) -> &mut Vec<Loan> {
+        &mut self.loans
+    }
+
+    /// Gold owed on active loans by a Village
+    pub fn debt(&self, village_id: VillageId) -> f64 {
+        self.active_loans()
+            .filter(|l| l.borrower == village_id)
+            .map(|l| l.balance)
+            .sum()
+    }
+
+    /// Gold owed to a Village on its active loans
+    pub fn credit(&self, village_id: VillageId) -> f64 {
+        self.active_loans()
+            .filter(|l| l.lender == Lender::Village(village_id))
+            .map(|l| l.balance)
+            .sum()
+    }
+
+    /// Gold owed on all active loans
+    pub fn total_debt(&self) -> f64 {
+        self.active_loans().map(|l| l.balance).sum()
+    }
+
+    pub fn loan_count(&self, status: LoanStatus) -> u32 {
+        self.loans.iter().filter(|l| l.status == status).count() as u32
+    }
+
+    fn active_loans(&self) -> impl Iterator<Item = &Loan> {
+        self.loans.iter().filter(|l| l.status == LoanStatus::Active)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use simulation::credit::*;
+
+    #[test]
+    fn installment_repays_principal_without_interest() {
+        assert_eq!(25.0, installment(100.0, 0.0, 4));
+    }
+
+    #[test]
+    fn installment_with_interest() {
+        let payment = installment(100.0, 0.1, 2);
+        let balance = (100.0 * 1.1 - payment) * 1.1 - payment;
+
+        assert!(balance.abs() < 1e-9);
+    }
+
+    #[test]
+    fn debt_and_credit() {
+        let mut credit_market = CreditMarket::new(0.01, 3);
+        credit_market.open(Lender::Village(1), 2, 50.0, 0.0, 5, (ResourceType::Food, 10.0));
+        credit_market.open(Lender::Central, 2, 20.0, 0.0, 5, (ResourceType::Food, 10.0));
+
+        assert_eq!(70.0, credit_market.debt(2));
+        assert_eq!(50.0, credit_market.credit(1));
+        assert_eq!(0.0, credit_market.debt(1));
+        assert_eq!(70.0, credit_market.total_debt());
+        assert_eq!(2, credit_market.loan_count(LoanStatus::Active));
+    }
+}
@@ -0,0 +1,127 @@
+use village::*;
+use village::resource::*;
+use village::worker::*;
+use village::mortality::DeathCause;
+use village::events::VillageEvent;
+use village::shock::ShockKind;
+use village_mind::trade_request::TradeType;
+
+/// Something that happened during `Simulation::simulate`
+#[derive(Copy, Clone, PartialEq, Debug)]
+pub enum Event {
+    TickStarted { tick: u32 },
+    /// A round of the central market is about to clear the trade requests
+    RoundStarted { tick: u32, round: u32 },
+    WorkerBorn { village_id: VillageId, worker_id: WorkerId },
+    WorkerDied { village_id: VillageId, worker_id: WorkerId, cause: DeathCause },
+    /// Resource id is 0 when the worker is idle or given a task other than a resource
+    WorkerReassigned { village_id: VillageId, worker_id: WorkerId, task: Task, resource_id: ResourceId },
+    ResourceCollected { village_id: VillageId, resource_id: ResourceId, resource_type: ResourceType, amount: f64 },
+    TradeSubmitted { village_id: VillageId, trade_type: TradeType, resource_type: ResourceType, amount: u32 },
+    /// Goods matched between two villages and paid for at the price
+    TradeFilled { buyer: VillageId, seller: VillageId, resource_type: ResourceType, amount: u32, price: u32 },
+    PriceChanged { resource_type: ResourceType, from: u32, to: u32 },
+    ShockStarted { village_id: VillageId, kind: ShockKind, duration: u32 },
+}
+
+impl Event {
+    pub fn from_village(village_id: VillageId, village_event: VillageEvent) -> Event {
+        match village_event {
+            VillageEvent::WorkerBorn { worker_id } => Event::WorkerBorn { village_id: village_id, worker_id: worker_id },
+            VillageEvent::WorkerDied { worker_id, cause } => Event::WorkerDied { village_id: village_id, worker_id: worker_id, cause: cause },
+            VillageEvent::WorkerReassigned { worker_id, task, resource_id } => Event::WorkerReassigned {
+                village_id: village_id,
+                worker_id: worker_id,
+                task: task,
+                resource_id: resource_id,
+            },
+            VillageEvent::ResourceCollected { resource_id, resource_type, amount } => Event::ResourceCollected {
+                village_id: village_id,
+                resource_id: resource_id,
+                resource_type: resource_type,
+                amount: amount,
+            },
+        }
+    }
+}
+
+pub type SubscriberId = u32;
+pub type Subscriber = Box<dyn FnMut(&Event)>;
+
+/// Passes every published event to each subscriber, in the order they subscribed
+pub struct EventBus {
+    subscribers: Vec<(SubscriberId, Subscriber)>,
+    subscriber_id_counter: u32,
+}
+
+impl Default for EventBus {
+    fn default() -> EventBus {
+        EventBus::new()
+    }
+}
+
+impl EventBus {
+    pub fn new() -> EventBus {
+        EventBus {
+            subscribers: vec!(),
+            subscriber_id_counter: 0,
+        }
+    }
+
+    /// Subscriber id values start at 1 and auto increment in subsequent invocations
+    /// Returns the id of the subscriber
+    pub fn subscribe(&mut self, subscriber: Subscriber) -> SubscriberId {
+        self.subscriber_id_counter += 1;
+        self.subscribers.push((self.subscriber_id_counter, subscriber));
+
+        self.subscriber_id_counter
+    }
+
+    pub fn unsubscribe(&mut self, subscriber_id: SubscriberId) -> Result<(), &'static str> {
+        let subscriber_count = self.subscribers.len();
+        self.subscribers.retain(|s| s.0 != subscriber_id);
+
+        if self.subscribers.len() < subscriber_count {
+            Ok(())
+        } else {
+            Err("Invalid Subscriber ID")
+        }
+    }
+
+    pub fn publish(&mut self, event: Event) {
+        for &mut (_, ref mut subscriber) in self.subscribers.iter_mut() {
+            subscriber(&event);
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use simulation::events::*;
+    use std::cell::RefCell;
+    use std::rc::Rc;
+
+    #[test]
+    fn publish_to_subscribers() {
+        let mut event_bus = EventBus::new();
+        let received = Rc::new(RefCell::new(vec!()));
+        let first = received.clone();
+        let second = received.clone();
+        event_bus.subscribe(Box::new(move |e: &Event| first.borrow_mut().push((1, *e))));
+        let subscriber_id = event_bus.subscribe(Box::new(move |e: &Event| second.borrow_mut().push((2, *e))));
+
+        event_bus.publish(Event::TickStarted { tick: 1 });
+        event_bus.unsubscribe(subscriber_id).unwrap();
+        event_bus.publish(Event::TickStarted { tick: 2 });
+
+        assert_eq!(vec!((1, Event::TickStarted { tick: 1 }), (2, Event::TickStarted { tick: 1 }), (1, Event::TickStarted { tick: 2 })), *received.borrow());
+        assert!(event_bus.unsubscribe(subscriber_id).is_err());
+    }
+
+    #[test]
+    fn from_village() {
+        let event = Event::from_village(3, VillageEvent::WorkerBorn { worker_id: 1 });
+
+        assert_eq!(Event::WorkerBorn { village_id: 3, worker_id: 1 }, event);
+    }
+}
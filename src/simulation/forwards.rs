@@ -0,0 +1,133 @@
+use village::VillageId;
+use village::resource::ResourceType;
+
+pub type ContractId = u32;
+
+pub const DEFAULT_PENALTY_RATE: f64 = 0.2;
+
+#[derive(Copy, Clone, PartialEq, Eq, Hash, Debug)]
+pub enum ContractStatus {
+    Open,
+    Delivered,
+    /// The seller did not have the goods at maturity
+    SellerDefaulted,
+    /// The buyer did not have the gold at maturity
+    BuyerDefaulted,
+}
+
+/// Agreement to exchange goods at a price locked when it is made, on a later tick
+pub struct ForwardContract {
+    pub contract_id: ContractId,
+    pub buyer: VillageId,
+    pub seller: VillageId,
+    pub resource_type: ResourceType,
+    pub amount: u32,
+    /// Gold paid for each unit at maturity
+    pub price: u32,
+    /// Tick on which the goods are delivered
+    pub maturity: u32,
+    pub status: ContractStatus,
+}
+
+impl ForwardContract {
+    /// Gold paid for all the goods at maturity
+    pub fn value(&self) -> f64 {
+        self.amount as f64 * self.price as f64
+    }
+}
+
+pub struct ForwardMarket {
+    /// Share of a contract's value the defaulting side pays the other side
+    pub penalty_rate: f64,
+
+    contracts: Vec<ForwardContract>,
+    contract_id_counter: u32,
+}
+
+impl ForwardMarket {
+    pub fn new(penalty_rate: f64) -> ForwardMarket {
+        ForwardMarket {
+            penalty_rate: penalty_rate,
+            contracts: vec!(),
+            contract_id_counter: 0,
+        }
+    }
+
+    /// Records a new contract, nothing is exchanged until maturity
+    /// Contract id values start at 1 and auto increment in subsequent invocations
+    /// Returns the id of the ForwardContract
+    pub fn open(&mut self, buyer: VillageId, seller: VillageId, resource_type: ResourceType, amount: u32, price: u32, maturity: u32) -> ContractId {
+        self.contract_id_counter += 1;
+        self.contracts.push(ForwardContract {
+            contract_id: self.contract_id_counter,
+            buyer: buyer,
+            seller: seller,
+            resource_type: resource_type,
+            amount: amount,
+            price: price,
+            maturity: maturity,
+            status: ContractStatus::Open,
+        });
+
+        self.contract_id_counter
+    }
+
+    pub fn contract(&self, contract_id: ContractId) -> Option<&ForwardContract> {
+        self.contracts.iter().find(|c| c.contract_id == contract_id)
+    }
+
+    pub fn contracts(&self) -> &Vec<ForwardContract> {
+        &self.contracts
+    }
+
+    pub fn contracts_mut(&mut self) -> &mut Vec<ForwardContract> {
+        &mut self.contracts
+    }
+
+    /// Goods of a resource a Village is due to receive on open contracts, less the goods it is due to deliver
+    pub fn position(&self, village_id: VillageId, resource_type: ResourceType) -> i64 {
+        self.contracts.iter()
+            .filter(|c| c.status == ContractStatus::Open && c.resource_type == resource_type)
+            .map(|c| {
+                if c.buyer == village_id {
+                    c.amount as i64
+                } else if c.seller == village_id {
+                    -(c.amount as i64)
+                } else {
+                    0
+                }
+            })
+            .sum()
+    }
+
+    pub fn contract_count(&self, status: ContractStatus) -> u32 {
+        self.contracts.iter().filter(|c| c.status == status).count() as u32
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use simulation::forwards::*;
+
+    #[test]
+    fn open_contract_ids() {
+        let mut forward_market = ForwardMarket::new(0.2);
+
+        assert_eq!(1, forward_market.open(1, 2, ResourceType::Food, 10, 5, 3));
+        assert_eq!(2, forward_market.open(2, 1, ResourceType::Wood, 4, 5, 3));
+        assert_eq!(50.0, forward_market.contract(1).unwrap().value());
+        assert_eq!(2, forward_market.contract_count(ContractStatus::Open));
+    }
+
+    #[test]
+    fn position() {
+        let mut forward_market = ForwardMarket::new(0.2);
+        forward_market.open(1, 2, ResourceType::Food, 10, 5, 3);
+        forward_market.open(3, 1, ResourceType::Food, 4, 5, 3);
+        forward_market.open(1, 2, ResourceType::Wood, 7, 5, 3);
+
+        assert_eq!(6, forward_market.position(1, ResourceType::Food));
+        assert_eq!(-10, forward_market.position(2, ResourceType::Food));
+        assert_eq!(0, forward_market.position(3, ResourceType::Wood));
+    }
+}
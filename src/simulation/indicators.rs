@@ -0,0 +1,276 @@
+use simulation::recording::*;
+use simulation::monetary_authority::{price_index, inflation};
+use village::VillageId;
+use village::resource::ResourceType;
+use std::collections::HashMap;
+
+/// Ticks of price history volatility is measured over
+pub const DEFAULT_VOLATILITY_WINDOW: u32 = 10;
+
+/// Goods valued at the given prices, gold being worth one
+pub fn value(goods: &HashMap<ResourceType, f64>, prices: &HashMap<ResourceType, u32>) -> f64 {
+    goods.iter()
+        .map(|(r, amount)| {
+            let price = if *r == ResourceType::Gold { 1.0 } else { *prices.get(r).unwrap_or(&0) as f64 };
+            amount.max(0.0) * price
+        })
+        .sum()
+}
+
+/// Gini coefficient of the values, 0.0 when they are all equal and close to 1.0 when one holds everything
+pub fn gini(values: &[f64]) -> f64 {
+    let mut values: Vec<f64> = values.iter().map(|v| v.max(0.0)).collect();
+    let total: f64 = values.iter().sum();
+    if values.is_empty() || total <= 0.0 {
+        return 0.0;
+    }
+
+    values.sort_by(f64::total_cmp);
+    let n = values.len() as f64;
+    let weighted: f64 = values.iter().enumerate().map(|(i, v)| (i + 1) as f64 * v).sum();
+
+    2.0 * weighted / (n * total) - (n + 1.0) / n
+}
+
+/// Standard deviation of the log changes in a resource's price over the last `window` ticks up to the given record
+pub fn volatility(recording: &[TickRecord], index: usize, resource_type: ResourceType, window: u32) -> f64 {
+    let start = index.saturating_sub(window.max(1) as usize);
+    let changes: Vec<f64> = (start..index)
+        .filter_map(|i| {
+            let previous = *recording[i].prices.get(&resource_type)? as f64;
+            let current = *recording[i + 1].prices.get(&resource_type)? as f64;
+            if previous > 0.0 && current > 0.0 { Some((current / previous).ln()) } else { None }
+        })
+        .collect();
+    if changes.len() < 2 {
+        return 0.0;
+    }
+
+    let mean = changes.iter().sum::<f64>() / changes.len() as f64;
+    (changes.iter().map(|c| (c - mean).powi(2)).sum::<f64>() / (changes.len() - 1) as f64).sqrt()
+}
+
+fn unemployment(idle_workers: u32, workers: u32) -> f64 {
+    if workers == 0 { 0.0 } else { idle_workers as f64 / workers as f64 }
+}
+
+pub struct VillageIndicators {
+    pub village_id: VillageId,
+    /// Goods collected during the tick, valued at the tick's prices
+    pub nominal_output: f64,
+    /// Goods collected during the tick, valued at the prices of the first tick recorded
+    pub real_output: f64,
+    /// Stockpile valued at the tick's prices, plus the savings of its workers
+    pub wealth: f64,
+    /// Share of workers that are idle
+    pub unemployment: f64,
+}
+
+pub struct MarketIndicators {
+    pub resource_type: ResourceType,
+    pub price: u32,
+    /// Goods sent between villages during the tick
+    pub trade_volume: u32,
+    /// Goods sent between villages during the tick, valued at the tick's price
+    pub trade_value: f64,
+    pub volatility: f64,
+}
+
+/// Aggregate measures of the economy at the end of a tick
+pub struct Indicators {
+    pub tick: u32,
+    pub nominal_output: f64,
+    pub real_output: f64,
+    pub price_index: f64,
+    /// Change in the price index since the previous tick
+    pub inflation: f64,
+    /// Inequality of wealth between villages
+    pub gini_villages: f64,
+    /// Inequality of savings between workers, across all villages
+    pub gini_workers: f64,
+    pub trade_volume: u32,
+    pub trade_value: f64,
+    pub unemployment: f64,
+    pub villages: Vec<VillageIndicators>,
+    /// One entry per resource other than gold
+    pub markets: Vec<MarketIndicators>,
+}
+
+impl Indicators {
+    /// Indicators of the tick at the given index of the recording, using the ticks before it for
+    /// real output, inflation and volatility
+    pub fn new(recording: &[TickRecord], index: usize, volatility_window: u32) -> Indicators {
+        let record = &recording[index];
+        let base_prices = &recording[0].prices;
+
+        let villages: Vec<VillageIndicators> = record.villages.iter()
+            .map(|v| VillageIndicators {
+                village_id: v.village_id,
+                nominal_output: value(&v.produced, &record.prices),
+                real_output: value(&v.produced, base_prices),
+                wealth: value(&v.stockpile, &record.prices) + v.worker_savings.iter().sum::<f64>(),
+                unemployment: unemployment(v.idle_worker_count, v.worker_count),
+            })
+            .collect();
+
+        let markets: Vec<MarketIndicators> = ResourceType::iterator()
+            .filter(|r| **r != ResourceType::Gold)
+            .map(|r| {
+                let price = *record.prices.get(r).unwrap_or(&0);
+                let trade_volume = *record.trade_volumes.get(r).unwrap_or(&0);
+                MarketIndicators {
+                    resource_type: *r,
+                    price: price,
+                    trade_volume: trade_volume,
+                    trade_value: trade_volume as f64 * price as f64,
+                    volatility: volatility(recording, index, *r, volatility_window),
+                }
+            })
+            .collect();
+
+        let worker_savings: Vec<f64> = record.villages.iter()
+            .flat_map(|v| v.worker_savings.iter().cloned())
+            .collect();
+
+        Indicators {
+            tick: record.tick,
+            nominal_output: villages.iter().map(|v| v.nominal_output).sum(),
+            real_output: villages.iter().map(|v| v.real_output).sum(),
+            price_index: price_index(&record.prices),
+            inflation: inflation(&recording[..index + 1], 1),
+            gini_villages: gini(&villages.iter().map(|v| v.wealth).collect::<Vec<f64>>()),
+            gini_workers: gini(&worker_savings),
+            trade_volume: markets.iter().map(|m| m.trade_volume).sum(),
+            trade_value: markets.iter().map(|m| m.trade_value).sum(),
+            unemployment: unemployment(
+                record.villages.iter().map(|v| v.idle_worker_count).sum(),
+                record.villages.iter().map(|v| v.worker_count).sum(),
+            ),
+            villages: villages,
+            markets: markets,
+        }
+    }
+}
+
+/// Aggregate indicators of every tick recorded, as CSV
+pub fn indicators_csv(recording: &[TickRecord], volatility_window: u32) -> String {
+    let mut csv = String::from("tick,nominal_output,real_output,price_index,inflation,gini_villages,gini_workers,trade_volume,trade_value,unemployment\n");
+    for index in 0..recording.len() {
+        let i = Indicators::new(recording, index, volatility_window);
+        csv.push_str(&format!("{},{},{},{},{},{},{},{},{},{}\n", i.tick, i.nominal_output, i.real_output, i.price_index, i.inflation,
+            i.gini_villages, i.gini_workers, i.trade_volume, i.trade_value, i.unemployment));
+    }
+    csv
+}
+
+/// Indicators of every Village at every tick recorded, as CSV
+pub fn village_indicators_csv(recording: &[TickRecord], volatility_window: u32) -> String {
+    let mut csv = String::from("tick,village_id,nominal_output,real_output,wealth,unemployment\n");
+    for index in 0..recording.len() {
+        let i = Indicators::new(recording, index, volatility_window);
+        for v in i.villages.iter() {
+            csv.push_str(&format!("{},{},{},{},{},{}\n", i.tick, v.village_id, v.nominal_output, v.real_output, v.wealth, v.unemployment));
+        }
+    }
+    csv
+}
+
+/// Indicators of every market at every tick recorded, as CSV
+pub fn market_indicators_csv(recording: &[TickRecord], volatility_window: u32) -> String {
+    let mut csv = String::from("tick,resource,price,trade_volume,trade_value,volatility\n");
+    for index in 0..recording.len() {
+        let i = Indicators::new(recording, index, volatility_window);
+        for m in i.markets.iter() {
+            csv.push_str(&format!("{},{:?},{},{},{},{}\n", i.tick, m.resource_type, m.price, m.trade_volume, m.trade_value, m.volatility));
+        }
+    }
+    csv
+}
+
+#[cfg(test)]
+mod tests {
+    use simulation::indicators::*;
+
+    fn record(tick: u32, food_price: u32, food_produced: f64, gold: (f64, f64)) -> TickRecord {
+        let mut prices = HashMap::new();
+        prices.insert(ResourceType::Food, food_price);
+        let village = |village_id: VillageId, gold: f64, workers: u32, idle: u32| VillageRecord {
+            village_id: village_id,
+            stockpile: vec!((ResourceType::Gold, gold)).into_iter().collect(),
+            produced: vec!((ResourceType::Food, food_produced)).into_iter().collect(),
+            worker_count: workers,
+            idle_worker_count: idle,
+            worker_savings: vec!(0.0; workers as usize),
+            technologies: vec!(),
+            research: None,
+            debt: 0.0,
+            credit: 0.0,
+            treasury: HashMap::new(),
+            tax_revenue: HashMap::new(),
+            public_spending: HashMap::new(),
+        };
+
+        TickRecord {
+            tick: tick,
+            prices: prices,
+            villages: vec!(village(1, gold.0, 3, 1), village(2, gold.1, 1, 0)),
+            total_debt: 0.0,
+            defaulted_loans: 0,
+            trade_volumes: vec!((ResourceType::Food, 4)).into_iter().collect(),
+        }
+    }
+
+    #[test]
+    fn gini_bounds() {
+        assert_eq!(0.0, gini(&[5.0, 5.0, 5.0]));
+        assert_eq!(0.0, gini(&[]));
+        assert!((gini(&[0.0, 0.0, 0.0, 10.0]) - 0.75).abs() < 1e-9);
+        assert!(gini(&[1.0, f64::NAN, 3.0]).is_finite());
+    }
+
+    #[test]
+    fn output_nominal_and_real() {
+        let recording = vec!(record(1, 5, 2.0, (0.0, 0.0)), record(2, 10, 2.0, (0.0, 0.0)));
+
+        let indicators = Indicators::new(&recording, 1, DEFAULT_VOLATILITY_WINDOW);
+
+        assert_eq!(40.0, indicators.nominal_output);
+        assert_eq!(20.0, indicators.real_output);
+        assert_eq!(1.0, indicators.inflation);
+        assert_eq!(40.0, indicators.markets[0].trade_value);
+        assert_eq!(0.25, indicators.unemployment);
+        assert_eq!(1.0 / 3.0, indicators.villages[0].unemployment);
+    }
+
+    #[test]
+    fn wealth_inequality() {
+        let mut recording = vec!(record(1, 5, 0.0, (30.0, 10.0)));
+        recording[0].villages[0].worker_savings = vec!(0.0, 0.0, 6.0);
+        recording[0].villages[1].worker_savings = vec!(2.0);
+
+        let indicators = Indicators::new(&recording, 0, DEFAULT_VOLATILITY_WINDOW);
+
+        assert_eq!(36.0, indicators.villages[0].wealth);
+        assert_eq!(0.25, indicators.gini_villages);
+        // workers of the same village hold different savings
+        assert_eq!(0.625, indicators.gini_workers);
+    }
+
+    #[test]
+    fn volatility_of_prices() {
+        let recording = vec!(record(1, 5, 0.0, (0.0, 0.0)), record(2, 5, 0.0, (0.0, 0.0)), record(3, 5, 0.0, (0.0, 0.0)));
+        assert_eq!(0.0, volatility(&recording, 2, ResourceType::Food, 10));
+
+        let recording = vec!(record(1, 4, 0.0, (0.0, 0.0)), record(2, 8, 0.0, (0.0, 0.0)), record(3, 4, 0.0, (0.0, 0.0)));
+        assert!((volatility(&recording, 2, ResourceType::Food, 10) - 2f64.ln() * 2f64.sqrt()).abs() < 1e-9);
+    }
+
+    #[test]
+    fn indicators_csv_rows() {
+        let recording = vec!(record(1, 5, 2.0, (0.0, 0.0)));
+
+        assert_eq!(2, indicators_csv(&recording, 10).lines().count());
+        assert_eq!(3, village_indicators_csv(&recording, 10).lines().count());
+        assert_eq!("tick,resource,price,trade_volume,trade_value,volatility\n1,Food,5,4,20,0\n1,Wood,0,0,0,0\n1,Stone,0,0,0,0\n1,Fish,0,0,0,0\n1,Fiat,0,0,0,0\n", market_indicators_csv(&recording, 10));
+    }
+}
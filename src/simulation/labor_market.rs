@@ -0,0 +1,97 @@
+use village::*;
+
+/// Workers stay in their Village unless a migration rate is set
+pub const DEFAULT_MIGRATION_RATE: f64 = 0.0;
+pub const DEFAULT_WAGE_WEIGHT: f64 = 1.0;
+pub const DEFAULT_FOOD_SECURITY_WEIGHT: f64 = 10.0;
+
+/// Workers that moved from one Village to another during a tick
+pub struct Migration {
+    pub tick: u32,
+    pub from: VillageId,
+    pub to: VillageId,
+    pub worker_count: u32,
+}
+
+pub struct LaborMarket {
+    /// Chance per tick of each worker leaving for a more attractive Village
+    pub migration_rate: f64,
+    /// Attractiveness of each gold of paid wage
+    pub wage_weight: f64,
+    /// Attractiveness of a fully fed Village
+    pub food_security_weight: f64,
+
+    migrations: Vec<Migration>,
+}
+
+impl LaborMarket {
+    pub fn new(migration_rate: f64, wage_weight: f64, food_security_weight: f64) -> LaborMarket {
+        LaborMarket {
+            migration_rate: migration_rate,
+            wage_weight: wage_weight,
+            food_security_weight: food_security_weight,
+            migrations: vec!(),
+        }
+    }
+
+    /// How much workers want to live in a Village, from the wage it paid and how well it can feed them
+    pub fn attractiveness(&self, village: &Village) -> f64 {
+        self.wage_weight * village.paid_wage() + self.food_security_weight * village.food_security()
+    }
+
+    pub fn record(&mut self, tick: u32, from: VillageId, to: VillageId, worker_count: u32) {
+        self.migrations.push(Migration {
+            tick: tick,
+            from: from,
+            to: to,
+            worker_count: worker_count,
+        });
+    }
+
+    pub fn migrations(&self) -> &Vec<Migration> {
+        &self.migrations
+    }
+
+    /// Workers gained minus workers lost by a Village over the whole run
+    pub fn net_migration(&self, village_id: VillageId) -> i64 {
+        self.migrations.iter().fold(0, |net, m| {
+            if m.to == village_id {
+                net + m.worker_count as i64
+            } else if m.from == village_id {
+                net - m.worker_count as i64
+            } else {
+                net
+            }
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use simulation::labor_market::*;
+    use village::resource::*;
+
+    #[test]
+    fn attractiveness_food_and_wage() {
+        let labor_market = LaborMarket::new(0.1, 1.0, 10.0);
+        let mut v = Village::new(|_| false);
+        v.create_worker(1);
+
+        assert_eq!(0.0, labor_market.attractiveness(&v));
+
+        *v.stockpile.get_mut(&ResourceType::Food).unwrap() = 1.0;
+
+        assert_eq!(10.0, labor_market.attractiveness(&v));
+    }
+
+    #[test]
+    fn net_migration() {
+        let mut labor_market = LaborMarket::new(0.1, 1.0, 10.0);
+        labor_market.record(1, 1, 2, 3);
+        labor_market.record(2, 2, 1, 1);
+
+        assert_eq!(-2, labor_market.net_migration(1));
+        assert_eq!(2, labor_market.net_migration(2));
+        assert_eq!(0, labor_market.net_migration(3));
+    }
+}
@@ -0,0 +1,142 @@
+use village::resource::ResourceType;
+use village::VillageId;
+use rng::Rng;
+use std::collections::HashMap;
+
+pub type CaravanId = u32;
+
+pub const DEFAULT_CARRYING_CAPACITY: u32 = 10;
+pub const DEFAULT_TRAVEL_TIME: u32 = 1;
+
+/// Goods bought on the market travelling to the buying village
+pub struct Caravan {
+    pub caravan_id: CaravanId,
+    pub destination: VillageId,
+    pub resource_type: ResourceType,
+    pub amount: u32,
+    pub arrival_tick: u32,
+    pub lost: bool,
+}
+
+pub struct Logistics {
+    pub carrying_capacity: u32,
+    pub travel_time: u32,
+    pub loss_chance: f64,
+
+    caravans: Vec<Caravan>,
+    caravan_id_counter: u32,
+    /// Goods of each resource dispatched during the current tick
+    dispatched: HashMap<ResourceType, u32>,
+}
+
+impl Logistics {
+    pub fn new(carrying_capacity: u32, travel_time: u32, loss_chance: f64) -> Logistics {
+        Logistics {
+            carrying_capacity: carrying_capacity,
+            travel_time: travel_time,
+            loss_chance: loss_chance,
+            caravans: vec!(),
+            caravan_id_counter: 0,
+            dispatched: HashMap::new(),
+        }
+    }
+
+    /// Sends goods to a village, splitting them across as many caravans as the carrying capacity requires
+    /// Caravan id values start at 1 and auto increment in subsequent invocations
+    /// Returns the ids of the dispatched caravans
+    pub fn dispatch(&mut self, destination: VillageId, resource_type: ResourceType, amount: u32, tick: u32) -> Vec<CaravanId> {
+        let mut caravan_ids = vec!();
+        let mut remaining = amount;
+        *self.dispatched.entry(resource_type).or_insert(0) += amount;
+
+        while remaining > 0 {
+            let load = remaining.min(self.carrying_capacity.max(1));
+            remaining -= load;
+
+            self.caravan_id_counter += 1;
+            self.caravans.push(Caravan {
+                caravan_id: self.caravan_id_counter,
+                destination: destination,
+                resource_type: resource_type,
+                amount: load,
+                arrival_tick: tick + self.travel_time,
+                lost: false,
+            });
+            caravan_ids.push(self.caravan_id_counter);
+        }
+
+        caravan_ids
+    }
+
+    /// Goods of a resource sent between villages since the counts were last cleared
+    pub fn dispatched(&self, resource_type: ResourceType) -> u32 {
+        *self.dispatched.get(&resource_type).unwrap_or(&0)
+    }
+
+    /// Resets the dispatched counts, done at the start of every tick
+    pub fn clear_dispatched(&mut self) {
+        self.dispatched.clear();
+    }
+
+    pub fn caravans(&self) -> &Vec<Caravan> {
+        &self.caravans
+    }
+
+    /// Removes and returns every caravan due at or before the given tick
+    /// Each caravan rolls against the loss chance on arrival, lost caravans are returned with `lost` set
+    pub(crate) fn unload(&mut self, tick: u32, rng: &mut Rng) -> Vec<Caravan> {
+        let mut arrived = vec!();
+        let mut index = 0;
+
+        while index < self.caravans.len() {
+            if self.caravans[index].arrival_tick <= tick {
+                let mut caravan = self.caravans.remove(index);
+                caravan.lost = rng.chance(self.loss_chance);
+                arrived.push(caravan);
+            } else {
+                index += 1;
+            }
+        }
+
+        arrived
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use simulation::logistics::*;
+
+    #[test]
+    fn dispatch_splits_by_capacity() {
+        let mut logistics = Logistics::new(10, 1, 0.0);
+        let caravan_ids = logistics.dispatch(1, ResourceType::Food, 25, 0);
+
+        assert_eq!(3, caravan_ids.len());
+        assert_eq!(5, logistics.caravans()[2].amount);
+        assert_eq!(25, logistics.dispatched(ResourceType::Food));
+        assert_eq!(0, logistics.dispatched(ResourceType::Wood));
+
+        logistics.clear_dispatched();
+        assert_eq!(0, logistics.dispatched(ResourceType::Food));
+    }
+
+    #[test]
+    fn unload_only_due_caravans() {
+        let mut logistics = Logistics::new(10, 2, 0.0);
+        let mut rng = Rng::new(0);
+        logistics.dispatch(1, ResourceType::Food, 5, 0);
+
+        assert_eq!(0, logistics.unload(1, &mut rng).len());
+        assert_eq!(1, logistics.unload(2, &mut rng).len());
+        assert_eq!(0, logistics.caravans().len());
+    }
+
+    #[test]
+    fn unload_certain_loss() {
+        let mut logistics = Logistics::new(10, 0, 1.0);
+        let mut rng = Rng::new(0);
+        logistics.dispatch(1, ResourceType::Wood, 5, 0);
+
+        assert!(logistics.unload(0, &mut rng)[0].lost);
+    }
+}
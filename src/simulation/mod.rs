@@ -1,10 +1,43 @@
 pub mod village_manager;
+pub mod logistics;
+pub mod labor_market;
+pub mod recording;
+pub mod credit;
+pub mod monetary_authority;
+pub mod trade_matching;
+pub mod barter;
+pub mod forwards;
+pub mod price_formation;
+pub mod events;
+pub mod shocks;
+pub mod indicators;
+pub mod builder;
 
 use self::village_manager::*;
+use self::logistics::*;
+use self::labor_market::*;
+use self::recording::*;
+use self::credit::*;
+use self::monetary_authority::*;
+use self::trade_matching::*;
+use self::barter::*;
+use self::forwards::*;
+use self::price_formation::*;
+use self::events::*;
+use self::shocks::*;
+use self::indicators::*;
 use village::*;
 use village::resource::*;
+use village::worker::WorkerId;
+use village::trade_policy::TradePolicy;
 use village_mind::*;
 use village_mind::trade_request::*;
+use village_mind::loan_request::*;
+use village_mind::barter_offer::*;
+use village_mind::forward_request::*;
+use village_mind::strategy::Strategy;
+use rng::Rng;
+use calendar::*;
 use std::cell::RefCell;
 use std::rc::Rc;
 use std::collections::HashMap;
@@ -18,75 +51,850 @@ pub enum PriceDirection {
 
 pub struct Simulation {
     village_managers: Vec<VillageManager>,
+    village_id_counter: u32,
     pub prices: HashMap<ResourceType, u32>,
     pub price_directions: HashMap<ResourceType, PriceDirection>,
+    pub logistics: Logistics,
+    pub labor_market: LaborMarket,
+    pub credit: CreditMarket,
+    pub forwards: ForwardMarket,
+    /// Central bank, when present it sets the central lender's interest rate every tick
+    pub monetary_authority: Option<MonetaryAuthority>,
+    pub tick: u32,
+    pub market_mode: MarketMode,
+    /// Most offers a barter negotiation may go through before it expires
+    pub max_barter_rounds: u32,
+    /// Most clearing rounds the central market runs in a tick, the tick moves on at the prices reached
+    pub max_clearing_rounds: u32,
+    /// Shocks that may strike each Village every tick
+    pub shock_rules: Vec<ShockRule>,
+    pub calendar: Calendar,
+
+    recording: Vec<TickRecord>,
+    quota_usage: QuotaUsage,
+    trade_diversions: Vec<TradeDiversion>,
+    negotiations: Vec<Negotiation>,
+    clearing_rounds: Vec<ClearingRound>,
+    clearing_round: u32,
+    event_bus: EventBus,
+    shocks: Vec<ShockRecord>,
+    submitted_trades: Vec<TradeRequest>,
+    /// Pairs of villages that exchanged goods during the current tick
+    trading_partners: Vec<(VillageId, VillageId)>,
+    rng: Rng,
+}
+
+impl Default for Simulation {
+    fn default() -> Simulation {
+        Simulation::new()
+    }
 }
 
 impl Simulation {
     pub fn new() -> Simulation {
+        Simulation::with_seed(0)
+    }
+
+    /// Creates a Simulation whose random events (e.g. lost caravans) are driven by the given seed
+    pub fn with_seed(seed: u64) -> Simulation {
 
         let mut simulation = Simulation {
             village_managers: vec!(),
+            village_id_counter: 0,
             prices: HashMap::new(),
             price_directions: HashMap::new(),
+            logistics: Logistics::new(DEFAULT_CARRYING_CAPACITY, DEFAULT_TRAVEL_TIME, 0.0),
+            labor_market: LaborMarket::new(DEFAULT_MIGRATION_RATE, DEFAULT_WAGE_WEIGHT, DEFAULT_FOOD_SECURITY_WEIGHT),
+            credit: CreditMarket::new(DEFAULT_CENTRAL_RATE, DEFAULT_MAX_MISSED_PAYMENTS),
+            forwards: ForwardMarket::new(DEFAULT_PENALTY_RATE),
+            monetary_authority: None,
+            tick: 0,
+            market_mode: MarketMode::Central,
+            max_barter_rounds: DEFAULT_MAX_BARTER_ROUNDS,
+            max_clearing_rounds: DEFAULT_MAX_CLEARING_ROUNDS,
+            shock_rules: vec!(),
+            calendar: Calendar::new(),
+            recording: vec!(),
+            quota_usage: HashMap::new(),
+            trade_diversions: vec!(),
+            negotiations: vec!(),
+            clearing_rounds: vec!(),
+            clearing_round: 0,
+            event_bus: EventBus::new(),
+            shocks: vec!(),
+            submitted_trades: vec!(),
+            trading_partners: vec!(),
+            rng: Rng::new(seed),
         };
 
-        //for each resource (other than gold), put the starting price at one gold
+        //for each resource (other than gold), put the starting price at five gold, fiat starts at par with gold
         for resource_type in ResourceType::iterator() {
             if *resource_type != ResourceType::Gold {
-                simulation.prices.insert(*resource_type, 5);
+                let price = if *resource_type == ResourceType::Fiat { 1 } else { 5 };
+                simulation.prices.insert(*resource_type, price);
                 simulation.price_directions.insert(*resource_type, PriceDirection::Equilibrium);
             }
         }
         simulation
     }
 
-    pub fn add_village(&mut self, village: Village) {
-        let villageRC = Rc::new(RefCell::new(village));
+    /// Adds a Village to this Simulation.
+    /// Village id values start at 1 and auto increment in subsequent invocations
+    /// Returns the id of the Village
+    pub fn add_village(&mut self, village: Village) -> VillageId {
+        self.village_id_counter += 1;
+        let village_rc = Rc::new(RefCell::new(village));
         let village_manager = VillageManager {
-            village: villageRC.clone(),
-            village_mind: VillageMind::new(villageRC.clone()),
+            village_id: self.village_id_counter,
+            village: village_rc.clone(),
+            village_mind: VillageMind::new(village_rc.clone()),
         };
 
         self.village_managers.push(village_manager);
+
+        self.village_id_counter
     }
 
     pub fn village_managers(&self) -> &Vec<VillageManager> {
         &self.village_managers
     }
 
+    pub fn village(&self, village_id: VillageId) -> Option<&VillageRef> {
+        self.village_managers.iter()
+            .find(|vm| vm.village_id == village_id)
+            .map(|vm| &vm.village)
+    }
+
     pub fn simulate(&mut self) {
+        self.tick += 1;
+        self.quota_usage.clear();
+        self.trading_partners.clear();
+        self.logistics.clear_dispatched();
+        self.clearing_round = 0;
+        self.event_bus.publish(Event::TickStarted { tick: self.tick });
+        self.strike_shocks();
+        self.deliver_caravans();
+
         // update the villages and village minds with the new information
+        let date = self.date();
         for vm in self.village_managers.iter_mut() {
+            vm.village.borrow_mut().set_date(date, &self.calendar);
             vm.village.borrow_mut().simulate();
             vm.village_mind.manage_village();
         }
+        self.publish_village_events();
 
-        // do the trading phase until no more trade request are given
-        let mut trading = true;
-        let mut trade_requests : Vec<TradeRequest> = Vec::new();
-
-        while trading {
-            trade_requests.clear();
+        self.migrate_workers();
+        self.service_loans();
 
-            for mut vm in self.village_managers.iter_mut() {
-                trade_requests.append(&mut vm.village_mind.trade());
+        let mut loan_requests = vec!();
+        for vm in self.village_managers.iter_mut() {
+            for loan_request in vm.village_mind.request_loans() {
+                loan_requests.push((vm.village_id, loan_request));
             }
+        }
+        for (borrower, loan_request) in loan_requests {
+            // refused requests are dropped, the mind can ask again next tick
+            let _ = self.grant_loan(borrower, &loan_request);
+        }
+
+        self.settle_forwards();
 
-            if trade_requests.len() == 0 {
-                trading = false;
+        let mut forward_requests = vec!();
+        for vm in self.village_managers.iter_mut() {
+            for forward_request in vm.village_mind.request_forwards() {
+                forward_requests.push((vm.village_id, forward_request));
             }
         }
+        for (buyer, forward_request) in forward_requests {
+            // refused requests are dropped, the mind can ask again next tick
+            let _ = self.open_forward(buyer, &forward_request);
+        }
+
+        match self.market_mode {
+            MarketMode::Central => {
+                // do the trading phase until no more trade request are given
+                let mut trading = true;
+                let mut trade_requests : Vec<TradeRequest> = Vec::new();
+                let mut tick_fills = TickFills::new();
+                // requests submitted from outside stand for the whole tick, like a mind asking again every round
+                let submitted_trades: Vec<TradeRequest> = self.submitted_trades.drain(..).collect();
+
+                while trading {
+                    trade_requests.clear();
+
+                    for vm in self.village_managers.iter_mut() {
+                        for mut trade_request in vm.village_mind.trade(&self.prices) {
+                            trade_request.village_id = vm.village_id;
+                            trade_requests.push(trade_request);
+                        }
+                    }
+                    trade_requests.extend(submitted_trades.iter().cloned());
+                    // requests made every round ask for the same goods, only what is still unfilled is traded again
+                    remove_filled(&mut trade_requests, &tick_fills);
+
+                    for trade_request in trade_requests.iter() {
+                        self.event_bus.publish(Event::TradeSubmitted {
+                            village_id: trade_request.village_id,
+                            trade_type: trade_request.trade_type,
+                            resource_type: trade_request.resource_type,
+                            amount: trade_request.request_amount,
+                        });
+                    }
+
+                    if trade_requests.is_empty() {
+                        trading = false;
+                    } else {
+                        self.handle_trades(&mut trade_requests);
+                        self.settle_trades(&mut trade_requests);
+                        add_filled(&trade_requests, &mut tick_fills);
+
+                        // stop once every resource has found its equilibrium price, or the round limit is hit
+                        trading = self.clearing_round < self.max_clearing_rounds
+                            && self.price_directions.values().any(|d| *d != PriceDirection::Equilibrium);
+                    }
+                }
+            },
+            MarketMode::Barter => self.negotiate_barters(),
+        }
+
+        self.spread_technologies();
 
         // update village minds
         for vm in self.village_managers.iter_mut() {
             vm.village_mind.manage_village();
         }
+        self.publish_village_events();
+
+        self.record();
+        self.apply_monetary_policy();
+    }
+
+    /// Date of the current tick
+    pub fn date(&self) -> Date {
+        self.calendar.date(self.tick)
+    }
+
+    /// Rolls every shock rule for every Village and starts the shocks that strike
+    /// Shocks that cannot affect a Village (e.g. a mine collapse without mines) are skipped
+    pub fn strike_shocks(&mut self) {
+        for vm in self.village_managers.iter() {
+            for rule in self.shock_rules.iter() {
+                if !self.rng.chance(rule.probability) {
+                    continue;
+                }
+
+                if let Ok(shock) = vm.village.borrow_mut().start_shock(rule.kind, rule.duration) {
+                    self.event_bus.publish(Event::ShockStarted { village_id: vm.village_id, kind: rule.kind, duration: rule.duration });
+                    self.shocks.push(ShockRecord {
+                        tick: self.tick,
+                        village_id: vm.village_id,
+                        shock: shock,
+                    });
+                }
+            }
+        }
+    }
+
+    /// Shocks that struck villages over the whole run, oldest first
+    pub fn shocks(&self) -> &Vec<ShockRecord> {
+        &self.shocks
+    }
+
+    /// Registers a function called with every event published from now on, e.g. by a logger or UI
+    /// Returns the id of the subscriber
+    pub fn subscribe<F>(&mut self, subscriber: F) -> SubscriberId where F: FnMut(&Event) + 'static {
+        self.event_bus.subscribe(Box::new(subscriber))
+    }
+
+    pub fn unsubscribe(&mut self, subscriber_id: SubscriberId) -> Result<(), &'static str> {
+        self.event_bus.unsubscribe(subscriber_id)
+    }
+
+    /// Publishes the events that happened inside each Village since they were last taken
+    fn publish_village_events(&mut self) {
+        for vm in self.village_managers.iter() {
+            for village_event in vm.village.borrow_mut().take_events() {
+                self.event_bus.publish(Event::from_village(vm.village_id, village_event));
+            }
+        }
+    }
+
+    /// Prices recorded at the end of every tick simulated so far, oldest first
+    pub fn price_history(&self) -> Vec<&HashMap<ResourceType, u32>> {
+        self.recording.iter().map(|r| &r.prices).collect()
+    }
+
+    /// Lets the monetary authority, if any, set the central lender's rate and issue fiat into the villages
+    pub fn apply_monetary_policy(&mut self) {
+        let (interest_rate, issued) = match self.monetary_authority {
+            Some(ref mut authority) => {
+                let decision = authority.decide(&self.recording);
+                (decision.interest_rate, decision.issued)
+            },
+            None => return,
+        };
+
+        self.credit.central_rate = interest_rate;
+
+        if issued > 0.0 && !self.village_managers.is_empty() {
+            let share = issued / self.village_managers.len() as f64;
+            for vm in self.village_managers.iter() {
+                *vm.village.borrow_mut().stockpile.get_mut(&ResourceType::Fiat).unwrap() += share;
+            }
+        }
+    }
+
+    /// Gold held by all villages and their workers, plus the fiat they hold valued at its gold price
+    pub fn money_supply(&self) -> f64 {
+        let fiat_price = *self.prices.get(&ResourceType::Fiat).unwrap() as f64;
+        self.village_managers.iter()
+            .map(|vm| {
+                let village = vm.village.borrow();
+                *village.stockpile.get(&ResourceType::Gold).unwrap() + village.household_savings()
+                    + *village.stockpile.get(&ResourceType::Fiat).unwrap() * fiat_price
+            })
+            .sum()
+    }
+
+    /// State at the end of every tick simulated so far, oldest first
+    pub fn recording(&self) -> &Vec<TickRecord> {
+        &self.recording
+    }
+
+    fn record(&mut self) {
+        let villages = self.village_managers.iter()
+            .map(|vm| {
+                let mut record = VillageRecord::new(vm.village_id, &vm.village.borrow());
+                record.debt = self.credit.debt(vm.village_id);
+                record.credit = self.credit.credit(vm.village_id);
+                record
+            })
+            .collect();
+
+        self.recording.push(TickRecord {
+            tick: self.tick,
+            prices: self.prices.clone(),
+            villages: villages,
+            total_debt: self.credit.total_debt(),
+            defaulted_loans: self.credit.loan_count(LoanStatus::Defaulted),
+            trade_volumes: ResourceType::iterator().map(|r| (*r, self.logistics.dispatched(*r))).collect(),
+        });
+    }
+
+    /// Indicators of the economy at the end of the last tick simulated, None before the first tick
+    pub fn indicators(&self) -> Option<Indicators> {
+        if self.recording.is_empty() {
+            return None;
+        }
+        Some(Indicators::new(&self.recording, self.recording.len() - 1, DEFAULT_VOLATILITY_WINDOW))
+    }
+
+    /// Lends gold to a Village, from another Village at the rate it lends at or from the central lender
+    /// Returns the id of the Loan
+    pub fn grant_loan(&mut self, borrower: VillageId, loan_request: &LoanRequest) -> Result<LoanId, &'static str> {
+        let borrower_village = match self.village(borrower) {
+            Some(village) => village.clone(),
+            None => return Err("Invalid Village ID"),
+        };
+        if loan_request.amount <= 0.0 {
+            return Err("Invalid loan amount");
+        }
+
+        let interest_rate = match loan_request.lender {
+            Lender::Central => self.credit.central_rate,
+            Lender::Village(lender) => {
+                if lender == borrower {
+                    return Err("Village cannot lend to itself");
+                }
+                let lender_village = match self.village(lender) {
+                    Some(village) => village,
+                    None => return Err("Invalid Village ID"),
+                };
+                let mut lender_village = lender_village.borrow_mut();
+                let interest_rate = match lender_village.lending_rate {
+                    Some(interest_rate) => interest_rate,
+                    None => return Err("Village is not lending"),
+                };
+                let gold = lender_village.stockpile.get_mut(&ResourceType::Gold).unwrap();
+                if *gold < loan_request.amount {
+                    return Err("Not enough gold");
+                }
+                *gold -= loan_request.amount;
+                interest_rate
+            },
+        };
+
+        *borrower_village.borrow_mut().stockpile.get_mut(&ResourceType::Gold).unwrap() += loan_request.amount;
+
+        Ok(self.credit.open(loan_request.lender, borrower, loan_request.amount, interest_rate, loan_request.term, loan_request.collateral))
+    }
+
+    /// Charges interest on every active loan and collects the installment due from the borrower
+    /// A borrower that misses too many payments in a row defaults and the lender seizes the collateral
+    pub fn service_loans(&mut self) {
+        let village_managers = &self.village_managers;
+        let village = |village_id: VillageId| village_managers.iter()
+            .find(|vm| vm.village_id == village_id)
+            .map(|vm| vm.village.clone());
+        let max_missed_payments = self.credit.max_missed_payments;
+
+        for loan in self.credit.loans_mut().iter_mut().filter(|l| l.status == LoanStatus::Active) {
+            let borrower = match village(loan.borrower) {
+                Some(borrower) => borrower,
+                None => continue,
+            };
+            let lender = match loan.lender {
+                Lender::Village(lender) => village(lender),
+                Lender::Central => None,
+            };
+
+            loan.balance += loan.balance * loan.interest_rate;
+            let due = loan.installment.min(loan.balance);
+            let mut borrower = borrower.borrow_mut();
+            let gold = borrower.stockpile.get_mut(&ResourceType::Gold).unwrap();
+
+            if *gold >= due {
+                *gold -= due;
+                loan.balance -= due;
+                loan.missed_payments = 0;
+                if let Some(lender) = lender {
+                    *lender.borrow_mut().stockpile.get_mut(&ResourceType::Gold).unwrap() += due;
+                }
+                if loan.balance <= 1e-9 {
+                    loan.status = LoanStatus::Repaid;
+                }
+                continue;
+            }
+
+            loan.missed_payments += 1;
+            if loan.missed_payments > max_missed_payments {
+                let (resource_type, amount) = loan.collateral;
+                let stock = borrower.stockpile.get_mut(&resource_type).unwrap();
+                let seized = amount.min(stock.max(0.0));
+                *stock -= seized;
+                if let Some(lender) = lender {
+                    *lender.borrow_mut().stockpile.get_mut(&resource_type).unwrap() += seized;
+                }
+                loan.status = LoanStatus::Defaulted;
+            }
+        }
+    }
+
+    /// Agrees a forward contract between the buying Village and the seller, if the seller's mind accepts it
+    /// Returns the id of the ForwardContract
+    pub fn open_forward(&mut self, buyer: VillageId, forward_request: &ForwardRequest) -> Result<ContractId, &'static str> {
+        let seller = forward_request.seller;
+        if buyer == seller {
+            return Err("Village cannot trade with itself");
+        }
+        if forward_request.amount == 0 || forward_request.delivery_in == 0 {
+            return Err("Invalid forward contract");
+        }
+        if forward_request.resource_type == ResourceType::Gold {
+            return Err("Gold cannot be bought");
+        }
+        match (self.village(buyer), self.village(seller)) {
+            (Some(buyer_village), Some(seller_village)) => {
+                let resource_type = forward_request.resource_type;
+                if buyer_village.borrow().trade_policy.is_embargoed(seller, resource_type) || seller_village.borrow().trade_policy.is_embargoed(buyer, resource_type) {
+                    return Err("Trade is embargoed");
+                }
+            },
+            _ => return Err("Invalid Village ID"),
+        }
+
+        let seller_manager = self.village_managers.iter_mut().find(|vm| vm.village_id == seller).unwrap();
+        if !seller_manager.village_mind.accept_forward(buyer, forward_request) {
+            return Err("Seller refused the contract");
+        }
+
+        Ok(self.forwards.open(buyer, seller, forward_request.resource_type, forward_request.amount, forward_request.price, self.tick + forward_request.delivery_in))
+    }
+
+    /// Settles every open forward contract that matures this tick
+    /// The seller's goods go to the buyer by caravan and the buyer pays the agreed price
+    /// A side that cannot deliver its goods or gold defaults and pays the other side a penalty from its gold
+    pub fn settle_forwards(&mut self) {
+        let village_managers = &self.village_managers;
+        let village = |village_id: VillageId| village_managers.iter()
+            .find(|vm| vm.village_id == village_id)
+            .map(|vm| vm.village.clone());
+        let penalty_rate = self.forwards.penalty_rate;
+        let tick = self.tick;
+        let logistics = &mut self.logistics;
+
+        for contract in self.forwards.contracts_mut().iter_mut().filter(|c| c.status == ContractStatus::Open && c.maturity <= tick) {
+            let (buyer, seller) = match (village(contract.buyer), village(contract.seller)) {
+                (Some(buyer), Some(seller)) => (buyer, seller),
+                _ => continue,
+            };
+            let mut buyer = buyer.borrow_mut();
+            let mut seller = seller.borrow_mut();
+            let amount = contract.amount as f64;
+            let value = contract.value();
+
+            contract.status = if *seller.stockpile.get(&contract.resource_type).unwrap() < amount {
+                ContractStatus::SellerDefaulted
+            } else if *buyer.stockpile.get(&ResourceType::Gold).unwrap() < value {
+                ContractStatus::BuyerDefaulted
+            } else {
+                ContractStatus::Delivered
+            };
+
+            let (payer, payee) = match contract.status {
+                ContractStatus::SellerDefaulted => (&mut seller, &mut buyer),
+                ContractStatus::BuyerDefaulted => (&mut buyer, &mut seller),
+                _ => {
+                    *seller.stockpile.get_mut(&contract.resource_type).unwrap() -= amount;
+                    *seller.stockpile.get_mut(&ResourceType::Gold).unwrap() += value;
+                    *buyer.stockpile.get_mut(&ResourceType::Gold).unwrap() -= value;
+                    buyer.add_inbound(contract.resource_type, amount);
+                    logistics.dispatch(contract.buyer, contract.resource_type, contract.amount, tick);
+                    continue;
+                },
+            };
+
+            let gold = payer.stockpile.get_mut(&ResourceType::Gold).unwrap();
+            let penalty = (value * penalty_rate).min(gold.max(0.0));
+            *gold -= penalty;
+            *payee.stockpile.get_mut(&ResourceType::Gold).unwrap() += penalty;
+        }
+    }
+
+    /// Matches the fulfilled part of each buy request with sellers, honouring the villages' trade policies,
+    /// and exchanges gold and goods at the current prices
+    /// Fulfilled amounts the trade policies prevented from matching are taken off the trade requests
+    /// Each fill is cut down to what the seller has in stock and the buyer can pay for, tariff and trade tax included
+    /// Sold goods leave the seller right away, bought goods are sent to the buyer by caravan
+    pub fn settle_trades(&mut self, trade_requests: &mut [TradeRequest]) {
+        let policies: HashMap<VillageId, TradePolicy> = self.village_managers.iter()
+            .map(|vm| (vm.village_id, vm.village.borrow().trade_policy.clone()))
+            .collect();
+
+        let quota_usage = self.quota_usage.clone();
+        let mut fills = match_trades(trade_requests, &policies, &mut self.quota_usage, None);
+        self.record_trade_diversions(trade_requests, &policies, &quota_usage, &fills);
+
+        for trade_request in trade_requests.iter_mut() {
+            trade_request.fulfilled_amount = 0;
+        }
+        let mut round_fills: HashMap<ResourceType, Vec<RoundFill>> = HashMap::new();
+
+        for fill in fills.iter_mut() {
+            let (buyer, seller) = match (self.village(fill.buyer), self.village(fill.seller)) {
+                (Some(buyer), Some(seller)) => (buyer.clone(), seller.clone()),
+                _ => continue,
+            };
+            let price = *self.prices.get(&fill.resource_type).unwrap();
+            let mut seller = seller.borrow_mut();
+            let mut buyer = buyer.borrow_mut();
+
+            // a fill never takes more than the seller has or costs more than the buyer can pay
+            let tariff_rate = buyer.trade_policy.tariff(fill.seller, fill.resource_type);
+            let unit_cost = price as f64 * (1.0 + tariff_rate + buyer.tax_policy.trade_rate);
+            let in_stock = seller.stockpile.get(&fill.resource_type).unwrap().max(0.0).floor() as u32;
+            let affordable = if unit_cost > 0.0 {
+                (buyer.stockpile.get(&ResourceType::Gold).unwrap().max(0.0) / unit_cost).floor() as u32
+            } else {
+                fill.amount
+            };
+            let deliverable = fill.amount.min(in_stock).min(affordable);
+            if let Some(used) = self.quota_usage.get_mut(&(fill.buyer, fill.seller, fill.resource_type)) {
+                *used -= fill.amount - deliverable;
+            }
+            fill.amount = deliverable;
+            if fill.amount == 0 {
+                continue;
+            }
+            trade_requests[fill.buy_index].fulfilled_amount += fill.amount;
+            trade_requests[fill.sell_index].fulfilled_amount += fill.amount;
+
+            let amount = fill.amount as f64;
+            let cost = amount * price as f64;
+
+            // the seller is paid before being taxed, so the tax can come out of the proceeds
+            *seller.stockpile.get_mut(&fill.resource_type).unwrap() -= amount;
+            *seller.stockpile.get_mut(&ResourceType::Gold).unwrap() += cost;
+            seller.pay_trade_tax(cost);
+
+            let tariff = cost * tariff_rate;
+            buyer.pay_trade_tax(cost);
+            *buyer.stockpile.get_mut(&ResourceType::Gold).unwrap() -= cost + tariff;
+            buyer.treasury.collect(ResourceType::Gold, tariff);
+            buyer.add_inbound(fill.resource_type, amount);
+            self.logistics.dispatch(fill.buyer, fill.resource_type, fill.amount, self.tick);
+            self.add_trading_partners(fill.buyer, fill.seller);
+            self.event_bus.publish(Event::TradeFilled {
+                buyer: fill.buyer,
+                seller: fill.seller,
+                resource_type: fill.resource_type,
+                amount: fill.amount,
+                price: price,
+            });
+            round_fills.entry(fill.resource_type).or_default().push(RoundFill {
+                buyer: fill.buyer,
+                seller: fill.seller,
+                amount: fill.amount,
+                price: price,
+            });
+        }
+
+        // the logged rounds show what was settled, not just what cleared at the price
+        let (tick, clearing_round) = (self.tick, self.clearing_round);
+        for round in self.clearing_rounds.iter_mut().filter(|r| r.tick == tick && r.round == clearing_round) {
+            round.settle(trade_requests, round_fills.remove(&round.resource_type).unwrap_or_default());
+        }
+    }
+
+    /// Hands a Village's decisions over to a strategy, e.g. one written outside the engine
+    pub fn set_strategy(&mut self, village_id: VillageId, strategy: Box<dyn Strategy>) -> Result<(), &'static str> {
+        match self.village_managers.iter_mut().find(|vm| vm.village_id == village_id) {
+            Some(vm) => {
+                vm.village_mind.set_strategy(strategy);
+                Ok(())
+            },
+            None => Err("Invalid Village ID"),
+        }
+    }
+
+    /// Submits a trade request on behalf of a Village from outside its mind, e.g. by hand
+    /// The request joins the mind's requests in every clearing round of the next tick, until it is filled
+    pub fn submit_trade(&mut self, village_id: VillageId, mut trade_request: TradeRequest) -> Result<(), &'static str> {
+        if self.village(village_id).is_none() {
+            return Err("Invalid Village ID");
+        }
+        if self.market_mode == MarketMode::Barter {
+            return Err("Trades are not cleared in barter mode");
+        }
+        if trade_request.resource_type == ResourceType::Gold {
+            return Err("Gold cannot be traded");
+        }
+        if trade_request.request_amount == 0 {
+            return Err("Invalid trade amount");
+        }
+
+        trade_request.village_id = village_id;
+        self.submitted_trades.push(trade_request);
+        Ok(())
+    }
+
+    /// Lets every village mind make barter offers and negotiates each with the mind of the partner
+    /// Accepted offers are exchanged directly, with the goods of both villages sent by caravan
+    pub fn negotiate_barters(&mut self) {
+        let mut offers = vec!();
+        for vm in self.village_managers.iter_mut() {
+            for mut offer in vm.village_mind.propose_barters() {
+                offer.from = vm.village_id;
+                offers.push(offer);
+            }
+        }
+
+        for offer in offers {
+            if offer.from == offer.to || self.village(offer.to).is_none() {
+                continue;
+            }
+
+            let negotiation = if self.is_barter_blocked(&offer) {
+                Negotiation {
+                    tick: self.tick,
+                    offers: vec!(offer),
+                    outcome: NegotiationOutcome::Blocked,
+                }
+            } else {
+                let village_managers = &mut self.village_managers;
+                let mut negotiation = negotiate(self.tick, offer, self.max_barter_rounds, |o| {
+                    let vm = village_managers.iter_mut().find(|vm| vm.village_id == o.to).unwrap();
+                    vm.village_mind.respond_to_barter(o)
+                });
+
+                if negotiation.outcome == NegotiationOutcome::Accepted && !self.exchange_barter(negotiation.final_offer()) {
+                    negotiation.outcome = NegotiationOutcome::Failed;
+                }
+                negotiation
+            };
+
+            self.negotiations.push(negotiation);
+        }
+    }
+
+    /// Barter is blocked when either village embargoes the other on either of the goods
+    fn is_barter_blocked(&self, offer: &BarterOffer) -> bool {
+        let from = self.village(offer.from).unwrap().borrow();
+        let to = self.village(offer.to).unwrap().borrow();
+
+        [offer.give.0, offer.take.0].iter().any(|r| {
+            from.trade_policy.is_embargoed(offer.to, *r) || to.trade_policy.is_embargoed(offer.from, *r)
+        })
+    }
+
+    /// Exchanges the goods of an accepted barter offer, if both villages have them
+    /// Returns whether the exchange took place
+    pub fn exchange_barter(&mut self, offer: &BarterOffer) -> bool {
+        let (from, to) = match (self.village(offer.from), self.village(offer.to)) {
+            (Some(from), Some(to)) => (from.clone(), to.clone()),
+            _ => return false,
+        };
+        let mut from = from.borrow_mut();
+        let mut to = to.borrow_mut();
+        let (give_type, give_amount) = offer.give;
+        let (take_type, take_amount) = offer.take;
+
+        if *from.stockpile.get(&give_type).unwrap() < give_amount as f64 || *to.stockpile.get(&take_type).unwrap() < take_amount as f64 {
+            return false;
+        }
+
+        *from.stockpile.get_mut(&give_type).unwrap() -= give_amount as f64;
+        to.add_inbound(give_type, give_amount as f64);
+        self.logistics.dispatch(offer.to, give_type, give_amount, self.tick);
+
+        *to.stockpile.get_mut(&take_type).unwrap() -= take_amount as f64;
+        from.add_inbound(take_type, take_amount as f64);
+        self.logistics.dispatch(offer.from, take_type, take_amount, self.tick);
+        self.add_trading_partners(offer.from, offer.to);
+
+        true
+    }
+
+    fn add_trading_partners(&mut self, a: VillageId, b: VillageId) {
+        let pair = (a.min(b), a.max(b));
+        if !self.trading_partners.contains(&pair) {
+            self.trading_partners.push(pair);
+        }
+    }
+
+    /// Villages that exchanged goods this tick learn from each other, speeding up research
+    /// on technologies the other village already knows
+    pub fn spread_technologies(&mut self) {
+        for &(a, b) in self.trading_partners.iter() {
+            let (a, b) = match (self.village(a), self.village(b)) {
+                (Some(a), Some(b)) => (a.clone(), b.clone()),
+                _ => continue,
+            };
+            let known_by_a = a.borrow().technologies().clone();
+            let known_by_b = b.borrow().technologies().clone();
+            a.borrow_mut().learn_from_partner(&known_by_b);
+            b.borrow_mut().learn_from_partner(&known_by_a);
+        }
+    }
+
+    /// Barter negotiations over the whole run, oldest first
+    pub fn negotiations(&self) -> &Vec<Negotiation> {
+        &self.negotiations
+    }
+
+    /// Every round of the central market over the whole run, oldest first
+    /// Export with `price_formation::rounds_csv` or `price_formation::schedules_csv`
+    pub fn clearing_rounds(&self) -> &Vec<ClearingRound> {
+        &self.clearing_rounds
+    }
+
+    /// Amounts demanded and supplied at each price tried for a resource during a tick
+    pub fn supply_demand_schedule(&self, tick: u32, resource_type: ResourceType) -> Vec<SchedulePoint> {
+        schedule(&self.clearing_rounds, tick, resource_type)
+    }
+
+    /// Compares the fills against matching without each kind of trade policy and records the differences
+    fn record_trade_diversions(&mut self, trade_requests: &[TradeRequest], policies: &HashMap<VillageId, TradePolicy>, quota_usage: &QuotaUsage, fills: &[Fill]) {
+        let actual = bilateral_volumes(fills);
+
+        for policy in [PolicyKind::Tariff, PolicyKind::Quota, PolicyKind::Embargo].iter() {
+            let without_policy = bilateral_volumes(&match_trades(trade_requests, policies, &mut quota_usage.clone(), Some(*policy)));
+            let mut pairs: Vec<&(VillageId, VillageId, ResourceType)> = actual.keys().chain(without_policy.keys()).collect();
+            pairs.sort_by_key(|&&(buyer, seller, resource_type)| (buyer, seller, resource_type as u32));
+            pairs.dedup();
+
+            for &&(buyer, seller, resource_type) in pairs.iter() {
+                let key = (buyer, seller, resource_type);
+                let volume_change = *actual.get(&key).unwrap_or(&0) as i64 - *without_policy.get(&key).unwrap_or(&0) as i64;
+                if volume_change != 0 {
+                    self.trade_diversions.push(TradeDiversion {
+                        tick: self.tick,
+                        policy: *policy,
+                        buyer: buyer,
+                        seller: seller,
+                        resource_type: resource_type,
+                        volume_change: volume_change,
+                    });
+                }
+            }
+        }
+    }
+
+    /// Changes in bilateral trade caused by each kind of trade policy over the whole run
+    pub fn trade_diversions(&self) -> &Vec<TradeDiversion> {
+        &self.trade_diversions
+    }
+
+    /// Moves workers from each Village towards the most attractive other Village, if it is more attractive
+    /// Attractiveness is measured once for all villages before anyone moves
+    pub fn migrate_workers(&mut self) {
+        let attractiveness: Vec<(VillageId, f64)> = self.village_managers.iter()
+            .map(|vm| (vm.village_id, self.labor_market.attractiveness(&vm.village.borrow())))
+            .collect();
+        // only workers living in a Village at the start of the tick may leave it, so nobody moves twice
+        let residents: HashMap<VillageId, Vec<WorkerId>> = self.village_managers.iter()
+            .map(|vm| (vm.village_id, vm.village.borrow().workers().iter().map(|w| w.worker_id).collect()))
+            .collect();
+
+        for &(from, from_attractiveness) in attractiveness.iter() {
+            let best = attractiveness.iter()
+                .filter(|a| a.0 != from && self.village(a.0).unwrap().borrow().has_free_housing())
+                .fold(None, |best: Option<(VillageId, f64)>, a| match best {
+                    Some(b) if b.1 >= a.1 => Some(b),
+                    _ => Some(*a),
+                });
+
+            let to = match best {
+                Some((to, to_attractiveness)) if to_attractiveness > from_attractiveness => to,
+                _ => continue,
+            };
+
+            let origin = self.village(from).unwrap().clone();
+            let destination = self.village(to).unwrap().clone();
+            let mut origin = origin.borrow_mut();
+            let mut destination = destination.borrow_mut();
+
+            let mut worker_count = 0;
+
+            for &worker_id in residents.get(&from).unwrap() {
+                if !destination.has_free_housing() {
+                    break;
+                }
+
+                if self.rng.chance(self.labor_market.migration_rate) {
+                    let mut worker = match origin.remove_worker(worker_id) {
+                        Some(worker) => worker,
+                        None => continue,
+                    };
+                    worker.id_history.push((from, worker_id));
+                    destination.adopt_worker(worker);
+                    worker_count += 1;
+                }
+            }
+
+            if worker_count > 0 {
+                self.labor_market.record(self.tick, from, to, worker_count);
+            }
+        }
+    }
+
+    /// Unloads every caravan that has reached its destination by the current tick
+    pub fn deliver_caravans(&mut self) {
+        for caravan in self.logistics.unload(self.tick, &mut self.rng) {
+            if let Some(village) = self.village(caravan.destination) {
+                village.borrow_mut().receive_inbound(caravan.resource_type, caravan.amount as f64, caravan.lost);
+            }
+        }
     }
 
-    pub fn handle_trades(&mut self, trade_requests: &mut Vec<TradeRequest>) {
+    pub fn handle_trades(&mut self, trade_requests: &mut [TradeRequest]) {
+        self.clearing_round += 1;
+        self.event_bus.publish(Event::RoundStarted { tick: self.tick, round: self.clearing_round });
+
         // get the trade request for each resource type, excluding gold
         for resource_type in ResourceType::iterator().filter(|r| **r != ResourceType::Gold) {
+            let price_tried = *self.prices.get(resource_type).unwrap();
             // get the total number of buys and sells requested for a resource at the current price
             let mut buys = 0;
             let mut sells = 0;
@@ -131,7 +939,7 @@ impl Simulation {
                         for buy_request in buy_requests.iter_mut() {
                             buy_request.fulfilled_amount += 1;
                             sells -= 1;
-                            if sells <= 0 { break; }
+                            if sells == 0 { break; }
                         }
                     }
 
@@ -164,13 +972,21 @@ impl Simulation {
                         for sell_request in sell_requests.iter_mut() {
                             sell_request.fulfilled_amount += 1;
                             buys -= 1;
-                            if buys <= 0 {break;}
+                            if buys == 0 {break;}
                         }
                     }
 
                     self.price_directions.insert(*resource_type, PriceDirection::Equilibrium);
                 }
             }
+
+            let price = *self.prices.get(resource_type).unwrap();
+            if price != price_tried {
+                self.event_bus.publish(Event::PriceChanged { resource_type: *resource_type, from: price_tried, to: price });
+            }
+
+            let direction = *self.price_directions.get(resource_type).unwrap();
+            self.clearing_rounds.push(ClearingRound::new(self.tick, self.clearing_round, *resource_type, price_tried, direction, trade_requests));
         }
     }
 }
@@ -178,13 +994,14 @@ impl Simulation {
 #[cfg(test)]
 mod tests {
     use village::*;
-    use village_mind::*;
     use simulation::*;
-    use village::resource::*;
-    use village_mind::trade_request::*;
+    use village::shock::ShockKind;
+    use village::taxation::TaxPolicy;
+    use village_mind::strategy::Strategy;
+    use std::collections::HashMap;
 
     fn default_village() -> Village {
-        Village::new(|w: &worker::Worker| false)
+        Village::new(|_w: &worker::Worker| false)
     }
 
     #[test]
@@ -196,94 +1013,962 @@ mod tests {
     }
 
     #[test]
-    fn initial_resource_prices() {
+    fn add_village_ids() {
         let mut simulation = Simulation::new();
 
-        assert_eq!(5, *simulation.prices.get(&ResourceType::Food).unwrap());
+        assert_eq!(1, simulation.add_village(default_village()));
+        assert_eq!(2, simulation.add_village(default_village()));
+        assert!(simulation.village(2).is_some());
+        assert!(simulation.village(3).is_none());
     }
 
-    #[test]
-    fn initial_price_directions() {
-        let mut simulation = Simulation::new();
+    fn settled_food_trade(simulation: &mut Simulation, amount: u32) -> (VillageId, VillageId) {
+        let seller = simulation.add_village(default_village());
+        let buyer = simulation.add_village(default_village());
+        *simulation.village(seller).unwrap().borrow_mut().stockpile.get_mut(&ResourceType::Food).unwrap() = amount as f64;
+        *simulation.village(buyer).unwrap().borrow_mut().stockpile.get_mut(&ResourceType::Gold).unwrap() = (amount * 5) as f64;
 
-        assert_eq!(PriceDirection::Equilibrium, *simulation.price_directions.get(&ResourceType::Food).unwrap());
+        let mut trade_requests : Vec<TradeRequest> = Vec::new();
+        trade_requests.push(TradeRequest::new(TradeType::Sell, amount, ResourceType::Food));
+        trade_requests.push(TradeRequest::new(TradeType::Buy, amount, ResourceType::Food));
+        trade_requests[0].village_id = seller;
+        trade_requests[1].village_id = buyer;
+        simulation.handle_trades(&mut trade_requests);
+        simulation.settle_trades(&mut trade_requests);
+
+        (seller, buyer)
     }
 
     #[test]
-    fn handle_trades_basic_sell_and_buy() {
+    fn settle_trades_goods_in_transit() {
         let mut simulation = Simulation::new();
-        let mut trade_requests : Vec<TradeRequest> = Vec::new();
-        trade_requests.push(TradeRequest::new(TradeType::Buy, 1, ResourceType::Food));
-        trade_requests.push(TradeRequest::new(TradeType::Sell, 1, ResourceType::Food));
-        simulation.handle_trades(&mut trade_requests);
+        let (seller, buyer) = settled_food_trade(&mut simulation, 4);
+        let seller = simulation.village(seller).unwrap().borrow();
+        let buyer = simulation.village(buyer).unwrap().borrow();
 
-        assert_eq!(1, trade_requests.get(0).unwrap().fulfilled_amount);
-        assert_eq!(1, trade_requests.get(1).unwrap().fulfilled_amount);
-        assert_eq!(PriceDirection::Equilibrium, *simulation.price_directions.get(&ResourceType::Food).unwrap());
+        assert_eq!(0, *seller.stockpile.get(&ResourceType::Food).unwrap() as u32);
+        assert_eq!(20, *seller.stockpile.get(&ResourceType::Gold).unwrap() as u32);
+        assert_eq!(0, *buyer.stockpile.get(&ResourceType::Gold).unwrap() as u32);
+        assert_eq!(0, *buyer.stockpile.get(&ResourceType::Food).unwrap() as u32);
+        assert_eq!(4, buyer.inbound(ResourceType::Food) as u32);
     }
 
     #[test]
-    fn handle_trades_upward_price() {
+    fn simulate_delivers_caravans_on_arrival() {
         let mut simulation = Simulation::new();
-        let mut trade_requests : Vec<TradeRequest> = Vec::new();
-        trade_requests.push(TradeRequest::new(TradeType::Buy, 1, ResourceType::Food));
-        simulation.handle_trades(&mut trade_requests);
+        simulation.logistics = Logistics::new(10, 2, 0.0);
+        let (_, buyer) = settled_food_trade(&mut simulation, 12);
+        simulation.village(buyer).unwrap().borrow_mut().decay_rates.insert(ResourceType::Food, 0.0);
 
-        assert_eq!(0, trade_requests.get(0).unwrap().fulfilled_amount);
-        assert_eq!(PriceDirection::Upward, *simulation.price_directions.get(&ResourceType::Food).unwrap());
-        assert_eq!(6, *simulation.prices.get(&ResourceType::Food).unwrap());
+        assert_eq!(2, simulation.logistics.caravans().len());
+
+        simulation.simulate();
+        assert_eq!(12, simulation.village(buyer).unwrap().borrow().inbound(ResourceType::Food) as u32);
+
+        simulation.simulate();
+        let buyer = simulation.village(buyer).unwrap().borrow();
+        assert_eq!(0, buyer.inbound(ResourceType::Food) as u32);
+        assert_eq!(12, *buyer.stockpile.get(&ResourceType::Food).unwrap() as u32);
     }
 
     #[test]
-    fn handle_trades_downward_price() {
+    fn simulate_lost_caravans() {
         let mut simulation = Simulation::new();
-        let mut trade_requests : Vec<TradeRequest> = Vec::new();
-        trade_requests.push(TradeRequest::new(TradeType::Sell, 1, ResourceType::Food));
-        simulation.handle_trades(&mut trade_requests);
+        simulation.logistics = Logistics::new(10, 1, 1.0);
+        let (_, buyer) = settled_food_trade(&mut simulation, 4);
+        simulation.simulate();
 
-        assert_eq!(0, trade_requests.get(0).unwrap().fulfilled_amount);
-        assert_eq!(PriceDirection::Downward, *simulation.price_directions.get(&ResourceType::Food).unwrap());
-        assert_eq!(4, *simulation.prices.get(&ResourceType::Food).unwrap());
+        let buyer = simulation.village(buyer).unwrap().borrow();
+        assert_eq!(0, buyer.inbound(ResourceType::Food) as u32);
+        assert_eq!(0, *buyer.stockpile.get(&ResourceType::Food).unwrap() as u32);
     }
 
     #[test]
-    fn handle_trades_multiple_rounds_downward() {
+    fn migrate_workers_towards_wages() {
         let mut simulation = Simulation::new();
-        let mut trade_requests : Vec<TradeRequest> = Vec::new();
-        trade_requests.push(TradeRequest::new(TradeType::Sell, 1, ResourceType::Food));
-        simulation.handle_trades(&mut trade_requests);
-        simulation.handle_trades(&mut trade_requests);
-        simulation.handle_trades(&mut trade_requests);
+        simulation.labor_market.migration_rate = 1.0;
+        let poor = simulation.add_village(default_village());
+        let rich = simulation.add_village(default_village());
+        let worker_id = simulation.village(poor).unwrap().borrow_mut().create_worker(1);
+        {
+            let mut rich = simulation.village(rich).unwrap().borrow_mut();
+            rich.create_worker(1);
+            rich.wage = 1.0;
+            *rich.stockpile.get_mut(&ResourceType::Gold).unwrap() = 100.0;
+        }
 
-        trade_requests.push(TradeRequest::new(TradeType::Buy, 5, ResourceType::Food));
-        simulation.handle_trades(&mut trade_requests);
+        simulation.simulate();
 
-        assert_eq!(1, trade_requests.get(0).unwrap().fulfilled_amount);
-        assert_eq!(1, trade_requests.get(1).unwrap().fulfilled_amount);
-        assert_eq!(PriceDirection::Equilibrium, *simulation.price_directions.get(&ResourceType::Food).unwrap());
-        assert_eq!(2, *simulation.prices.get(&ResourceType::Food).unwrap());
+        assert_eq!(0, simulation.village(poor).unwrap().borrow().workers().len());
+        let rich_village = simulation.village(rich).unwrap().borrow();
+        let migrant = rich_village.worker(2).unwrap();
+        assert_eq!(vec!((poor, worker_id)), migrant.id_history);
+        assert_eq!(1, simulation.labor_market.migrations().len());
+        assert_eq!(1, simulation.labor_market.net_migration(rich));
     }
 
     #[test]
-    fn handle_trades_multiple_rounds_upward() {
+    fn migrate_workers_off_by_default() {
         let mut simulation = Simulation::new();
-        let mut trade_requests : Vec<TradeRequest> = Vec::new();
-        trade_requests.push(TradeRequest::new(TradeType::Buy, 1, ResourceType::Food));
-        simulation.handle_trades(&mut trade_requests);
-        simulation.handle_trades(&mut trade_requests);
-        simulation.handle_trades(&mut trade_requests);
+        let poor = simulation.add_village(default_village());
+        let rich = simulation.add_village(default_village());
+        simulation.village(poor).unwrap().borrow_mut().create_worker(1);
+        {
+            let mut rich = simulation.village(rich).unwrap().borrow_mut();
+            rich.create_worker(1);
+            rich.wage = 1.0;
+            *rich.stockpile.get_mut(&ResourceType::Gold).unwrap() = 100.0;
+        }
 
-        trade_requests.push(TradeRequest::new(TradeType::Sell, 5, ResourceType::Food));
-        simulation.handle_trades(&mut trade_requests);
+        simulation.simulate();
 
-        assert_eq!(1, trade_requests.get(0).unwrap().fulfilled_amount);
-        assert_eq!(1, trade_requests.get(1).unwrap().fulfilled_amount);
-        assert_eq!(PriceDirection::Equilibrium, *simulation.price_directions.get(&ResourceType::Food).unwrap());
-        assert_eq!(8, *simulation.prices.get(&ResourceType::Food).unwrap());
+        assert_eq!(1, simulation.village(poor).unwrap().borrow().workers().len());
+        assert_eq!(0, simulation.labor_market.migrations().len());
     }
 
     #[test]
-    fn handle_trades_price_minimum() {
+    fn migrate_workers_needs_housing() {
+        let mut simulation = Simulation::new();
+        simulation.labor_market.migration_rate = 1.0;
+        let poor = simulation.add_village(default_village());
+        let rich = simulation.add_village(default_village());
+        simulation.village(poor).unwrap().borrow_mut().create_worker(1);
+        simulation.village(poor).unwrap().borrow_mut().create_worker(1);
+        {
+            let mut rich = simulation.village(rich).unwrap().borrow_mut();
+            rich.base_housing = 2;
+            rich.create_worker(1);
+            rich.wage = 1.0;
+            *rich.stockpile.get_mut(&ResourceType::Gold).unwrap() = 100.0;
+        }
+
+        simulation.simulate();
+
+        assert_eq!(2, simulation.village(rich).unwrap().borrow().workers().len());
+        assert_eq!(1, simulation.village(poor).unwrap().borrow().workers().len());
+    }
+
+    #[test]
+    fn migrate_workers_equal_villages() {
+        let mut simulation = Simulation::new();
+        simulation.labor_market.migration_rate = 1.0;
+        let v1 = simulation.add_village(default_village());
+        let v2 = simulation.add_village(default_village());
+        simulation.village(v1).unwrap().borrow_mut().create_worker(1);
+        simulation.village(v2).unwrap().borrow_mut().create_worker(1);
+
+        simulation.simulate();
+
+        assert_eq!(0, simulation.labor_market.migrations().len());
+    }
+
+    #[test]
+    fn simulate_records_each_tick() {
+        let mut simulation = Simulation::new();
+        let v1 = simulation.add_village(default_village());
+        simulation.village(v1).unwrap().borrow_mut().create_worker(1);
+
+        simulation.simulate();
+        simulation.simulate();
+
+        assert_eq!(2, simulation.recording().len());
+        let record = &simulation.recording()[1];
+        assert_eq!(2, record.tick);
+        assert_eq!(5, *record.prices.get(&ResourceType::Food).unwrap());
+        assert_eq!(1, record.village(v1).unwrap().worker_count);
+    }
+
+    #[test]
+    fn simulate_records_research_progress() {
+        let mut simulation = Simulation::new();
+        let v1 = simulation.add_village(default_village());
+        {
+            let mut village = simulation.village(v1).unwrap().borrow_mut();
+            *village.stockpile.get_mut(&ResourceType::Gold).unwrap() = 20.0;
+            village.start_research(technology::Technology::Forestry).unwrap();
+            let w1 = village.create_worker(1);
+            village.assign_researcher(w1).unwrap();
+        }
+
+        for _ in 0..3 {
+            simulation.simulate();
+        }
+
+        let (technology, progress) = simulation.recording()[2].village(v1).unwrap().research.unwrap();
+        assert_eq!(technology::Technology::Forestry, technology);
+        assert_eq!(0.1, progress);
+    }
+
+    #[test]
+    fn central_loan_repaid_over_term() {
+        let mut simulation = Simulation::new();
+        simulation.credit.central_rate = 0.0;
+        let v1 = simulation.add_village(default_village());
+        let loan_id = simulation.grant_loan(v1, &LoanRequest::new(Lender::Central, 40.0, 4, (ResourceType::Stone, 10.0))).unwrap();
+
+        assert_eq!(40.0, *simulation.village(v1).unwrap().borrow().stockpile.get(&ResourceType::Gold).unwrap());
+
+        for _ in 0..4 {
+            simulation.service_loans();
+        }
+
+        assert_eq!(LoanStatus::Repaid, simulation.credit.loan(loan_id).unwrap().status);
+        assert_eq!(0.0, *simulation.village(v1).unwrap().borrow().stockpile.get(&ResourceType::Gold).unwrap());
+        assert_eq!(0.0, simulation.credit.debt(v1));
+    }
+
+    #[test]
+    fn village_loan_pays_interest_to_lender() {
+        let mut simulation = Simulation::new();
+        let lender = simulation.add_village(default_village());
+        let borrower = simulation.add_village(default_village());
+        let request = LoanRequest::new(Lender::Village(lender), 100.0, 10, (ResourceType::Stone, 10.0));
+
+        assert!(simulation.grant_loan(borrower, &request).is_err());
+
+        {
+            let mut lender = simulation.village(lender).unwrap().borrow_mut();
+            lender.lending_rate = Some(0.1);
+            *lender.stockpile.get_mut(&ResourceType::Gold).unwrap() = 100.0;
+        }
+        let loan_id = simulation.grant_loan(borrower, &request).unwrap();
+
+        assert_eq!(0.0, *simulation.village(lender).unwrap().borrow().stockpile.get(&ResourceType::Gold).unwrap());
+
+        simulation.service_loans();
+
+        let installment = simulation.credit.loan(loan_id).unwrap().installment;
+        assert_eq!(installment, *simulation.village(lender).unwrap().borrow().stockpile.get(&ResourceType::Gold).unwrap());
+        assert_eq!(110.0 - installment, simulation.credit.credit(lender));
+    }
+
+    #[test]
+    fn loan_default_seizes_collateral() {
+        let mut simulation = Simulation::new();
+        simulation.credit.max_missed_payments = 1;
+        let lender = simulation.add_village(default_village());
+        let borrower = simulation.add_village(default_village());
+        {
+            let mut lender = simulation.village(lender).unwrap().borrow_mut();
+            lender.lending_rate = Some(0.0);
+            *lender.stockpile.get_mut(&ResourceType::Gold).unwrap() = 10.0;
+        }
+        let loan_id = simulation.grant_loan(borrower, &LoanRequest::new(Lender::Village(lender), 10.0, 1, (ResourceType::Stone, 8.0))).unwrap();
+        {
+            let mut borrower = simulation.village(borrower).unwrap().borrow_mut();
+            *borrower.stockpile.get_mut(&ResourceType::Gold).unwrap() = 0.0;
+            *borrower.stockpile.get_mut(&ResourceType::Stone).unwrap() = 5.0;
+        }
+
+        simulation.service_loans();
+        assert_eq!(LoanStatus::Active, simulation.credit.loan(loan_id).unwrap().status);
+
+        simulation.service_loans();
+        assert_eq!(LoanStatus::Defaulted, simulation.credit.loan(loan_id).unwrap().status);
+        assert_eq!(0.0, *simulation.village(borrower).unwrap().borrow().stockpile.get(&ResourceType::Stone).unwrap());
+        assert_eq!(5.0, *simulation.village(lender).unwrap().borrow().stockpile.get(&ResourceType::Stone).unwrap());
+        assert_eq!(0.0, simulation.credit.total_debt());
+    }
+
+    #[test]
+    fn simulate_records_debt() {
+        let mut simulation = Simulation::new();
+        let v1 = simulation.add_village(default_village());
+        simulation.grant_loan(v1, &LoanRequest::new(Lender::Central, 50.0, 100, (ResourceType::Stone, 10.0))).unwrap();
+
+        simulation.simulate();
+
+        let record = &simulation.recording()[0];
+        assert!(record.total_debt > 0.0);
+        assert_eq!(record.total_debt, record.village(v1).unwrap().debt);
+        assert_eq!(0, record.defaulted_loans);
+    }
+
+    #[test]
+    fn simulate_without_monetary_authority() {
+        let mut simulation = Simulation::new();
+        simulation.simulate();
+
+        assert_eq!(DEFAULT_CENTRAL_RATE, simulation.credit.central_rate);
+    }
+
+    #[test]
+    fn simulate_monetary_authority_sets_rate_and_issues() {
+        let mut simulation = Simulation::new();
+        simulation.monetary_authority = Some(MonetaryAuthority::new(PolicyRule::FixedRate(0.05), 10.0, 1));
+        simulation.add_village(default_village());
+        simulation.add_village(default_village());
+
+        simulation.simulate();
+
+        assert_eq!(0.05, simulation.credit.central_rate);
+        assert_eq!(10.0, simulation.money_supply());
+        assert_eq!(1, simulation.monetary_authority.as_ref().unwrap().decisions().len());
+        for village_id in 1..3 {
+            let village = simulation.village(village_id).unwrap().borrow();
+            assert_eq!(5.0, *village.stockpile.get(&ResourceType::Fiat).unwrap());
+            assert_eq!(0.0, *village.stockpile.get(&ResourceType::Gold).unwrap());
+        }
+    }
+
+    #[test]
+    fn fiat_traded_for_gold() {
+        let mut simulation = Simulation::new();
+        simulation.monetary_authority = Some(MonetaryAuthority::new(PolicyRule::FixedRate(0.05), 10.0, 1));
+        let holder = simulation.add_village(default_village());
+        let buyer = simulation.add_village(trading_village(0.0, 20.0));
+        simulation.simulate();
+
+        simulation.submit_trade(holder, TradeRequest::new(TradeType::Sell, 5, ResourceType::Fiat)).unwrap();
+        simulation.submit_trade(buyer, TradeRequest::new(TradeType::Buy, 5, ResourceType::Fiat)).unwrap();
+        simulation.simulate();
+
+        let holder = simulation.village(holder).unwrap().borrow();
+        assert_eq!(5.0, *holder.stockpile.get(&ResourceType::Fiat).unwrap());
+        assert_eq!(5.0, *holder.stockpile.get(&ResourceType::Gold).unwrap());
+    }
+
+    #[test]
+    fn price_history() {
+        let mut simulation = Simulation::new();
+        simulation.simulate();
+        simulation.prices.insert(ResourceType::Food, 7);
+        simulation.simulate();
+
+        let history = simulation.price_history();
+        assert_eq!(5, *history[0].get(&ResourceType::Food).unwrap());
+        assert_eq!(7, *history[1].get(&ResourceType::Food).unwrap());
+    }
+
+    fn food_trade_requests(sellers: &[(VillageId, u32)], buyer: (VillageId, u32)) -> Vec<TradeRequest> {
+        let mut trade_requests : Vec<TradeRequest> = Vec::new();
+        for &(seller, amount) in sellers.iter() {
+            let mut trade_request = TradeRequest::new(TradeType::Sell, amount, ResourceType::Food);
+            trade_request.village_id = seller;
+            trade_requests.push(trade_request);
+        }
+        let mut trade_request = TradeRequest::new(TradeType::Buy, buyer.1, ResourceType::Food);
+        trade_request.village_id = buyer.0;
+        trade_requests.push(trade_request);
+        trade_requests
+    }
+
+    fn trading_village(food: f64, gold: f64) -> Village {
+        let mut village = default_village();
+        *village.stockpile.get_mut(&ResourceType::Food).unwrap() = food;
+        *village.stockpile.get_mut(&ResourceType::Gold).unwrap() = gold;
+        village
+    }
+
+    #[test]
+    fn settle_trades_capped_by_stock_and_gold() {
+        let mut simulation = Simulation::new();
+        let seller = simulation.add_village(trading_village(2.5, 0.0));
+        let buyer = simulation.add_village(trading_village(0.0, 100.0));
+
+        let mut trade_requests = food_trade_requests(&[(seller, 5)], (buyer, 5));
+        simulation.handle_trades(&mut trade_requests);
+        simulation.settle_trades(&mut trade_requests);
+
+        assert_eq!((2, 2), (trade_requests[0].fulfilled_amount, trade_requests[1].fulfilled_amount));
+        assert_eq!(0.5, *simulation.village(seller).unwrap().borrow().stockpile.get(&ResourceType::Food).unwrap());
+
+        let poor = simulation.add_village(trading_village(0.0, 12.0));
+        let rich = simulation.add_village(trading_village(100.0, 0.0));
+        let mut trade_requests = food_trade_requests(&[(rich, 5)], (poor, 5));
+        simulation.handle_trades(&mut trade_requests);
+        simulation.settle_trades(&mut trade_requests);
+
+        assert_eq!(2, trade_requests[1].fulfilled_amount);
+        assert_eq!(2.0, *simulation.village(poor).unwrap().borrow().stockpile.get(&ResourceType::Gold).unwrap());
+    }
+
+    #[test]
+    fn settle_trades_taxes_seller_proceeds() {
+        let mut simulation = Simulation::new();
+        let seller = simulation.add_village(trading_village(10.0, 0.0));
+        let buyer = simulation.add_village(trading_village(0.0, 100.0));
+        simulation.village(seller).unwrap().borrow_mut().tax_policy = TaxPolicy::new(0.0, 0.1, 0.0, false);
+
+        let mut trade_requests = food_trade_requests(&[(seller, 4)], (buyer, 4));
+        simulation.handle_trades(&mut trade_requests);
+        simulation.settle_trades(&mut trade_requests);
+
+        let seller = simulation.village(seller).unwrap().borrow();
+        assert_eq!(2.0, seller.treasury.funds(ResourceType::Gold));
+        assert_eq!(18.0, *seller.stockpile.get(&ResourceType::Gold).unwrap());
+    }
+
+    #[test]
+    fn settle_trades_logs_settled_fills() {
+        let mut simulation = Simulation::new();
+        let seller = simulation.add_village(trading_village(100.0, 0.0));
+        let buyer = simulation.add_village(trading_village(0.0, 12.0));
+
+        let mut trade_requests = food_trade_requests(&[(seller, 5)], (buyer, 5));
+        simulation.handle_trades(&mut trade_requests);
+        simulation.settle_trades(&mut trade_requests);
+
+        let round = simulation.clearing_rounds().iter().find(|r| r.resource_type == ResourceType::Food).unwrap();
+        assert_eq!(2, round.filled());
+        assert_eq!((5, 2), (round.orders[1].request_amount, round.orders[1].fulfilled_amount));
+        assert_eq!(1, round.fills.len());
+        assert_eq!((buyer, seller, 2, 5), (round.fills[0].buyer, round.fills[0].seller, round.fills[0].amount, round.fills[0].price));
+    }
+
+    #[test]
+    fn settle_trades_embargo_blocks_trade() {
+        let mut simulation = Simulation::new();
+        let seller = simulation.add_village(default_village());
+        let buyer = simulation.add_village(default_village());
+        simulation.village(buyer).unwrap().borrow_mut().trade_policy.embargo_all(seller);
+
+        let mut trade_requests = food_trade_requests(&[(seller, 3)], (buyer, 3));
+        simulation.handle_trades(&mut trade_requests);
+        simulation.settle_trades(&mut trade_requests);
+
+        assert_eq!(0, trade_requests[0].fulfilled_amount);
+        assert_eq!(0, trade_requests[1].fulfilled_amount);
+        assert_eq!(0.0, simulation.village(buyer).unwrap().borrow().inbound(ResourceType::Food));
+
+        let diversion = &simulation.trade_diversions()[0];
+        assert_eq!(PolicyKind::Embargo, diversion.policy);
+        assert_eq!(-3, diversion.volume_change);
+    }
+
+    #[test]
+    fn settle_trades_tariff_diverts_trade() {
+        let mut simulation = Simulation::new();
+        let taxed = simulation.add_village(trading_village(100.0, 100.0));
+        let untaxed = simulation.add_village(trading_village(100.0, 100.0));
+        let buyer = simulation.add_village(trading_village(100.0, 100.0));
+        simulation.village(buyer).unwrap().borrow_mut().trade_policy.set_tariff(taxed, ResourceType::Food, 0.5);
+
+        let mut trade_requests = food_trade_requests(&[(taxed, 2), (untaxed, 2)], (buyer, 2));
+        trade_requests[0].fulfilled_amount = 2;
+        trade_requests[1].fulfilled_amount = 0;
+        trade_requests[2].fulfilled_amount = 2;
+        simulation.settle_trades(&mut trade_requests);
+
+        assert_eq!(2, trade_requests[0].fulfilled_amount);
+        assert_eq!(5.0, simulation.village(buyer).unwrap().borrow().treasury.funds(ResourceType::Gold));
+
+        let mut trade_requests = food_trade_requests(&[(taxed, 2), (untaxed, 2)], (buyer, 2));
+        trade_requests[0].fulfilled_amount = 2;
+        trade_requests[1].fulfilled_amount = 2;
+        trade_requests[2].fulfilled_amount = 2;
+        simulation.settle_trades(&mut trade_requests);
+
+        assert_eq!(0, trade_requests[0].fulfilled_amount);
+        assert_eq!(2, trade_requests[1].fulfilled_amount);
+        let diversions: Vec<_> = simulation.trade_diversions().iter().filter(|d| d.policy == PolicyKind::Tariff).collect();
+        assert_eq!(2, diversions.len());
+        assert!(diversions.iter().any(|d| d.seller == taxed && d.volume_change == -2));
+        assert!(diversions.iter().any(|d| d.seller == untaxed && d.volume_change == 2));
+    }
+
+    #[test]
+    fn settle_trades_quota_per_tick() {
+        let mut simulation = Simulation::new();
+        let seller = simulation.add_village(trading_village(100.0, 100.0));
+        let buyer = simulation.add_village(trading_village(100.0, 100.0));
+        simulation.village(buyer).unwrap().borrow_mut().trade_policy.set_quota(seller, ResourceType::Food, 3);
+
+        let mut trade_requests = food_trade_requests(&[(seller, 2)], (buyer, 2));
+        simulation.handle_trades(&mut trade_requests);
+        simulation.settle_trades(&mut trade_requests);
+        assert_eq!(2, trade_requests[1].fulfilled_amount);
+
+        let mut trade_requests = food_trade_requests(&[(seller, 2)], (buyer, 2));
+        simulation.handle_trades(&mut trade_requests);
+        simulation.settle_trades(&mut trade_requests);
+        assert_eq!(1, trade_requests[1].fulfilled_amount);
+
+        simulation.simulate();
+
+        let mut trade_requests = food_trade_requests(&[(seller, 2)], (buyer, 2));
+        simulation.handle_trades(&mut trade_requests);
+        simulation.settle_trades(&mut trade_requests);
+        assert_eq!(2, trade_requests[1].fulfilled_amount);
+    }
+
+    fn barter_villages(simulation: &mut Simulation) -> (VillageId, VillageId) {
+        let v1 = simulation.add_village(default_village());
+        let v2 = simulation.add_village(default_village());
+        *simulation.village(v1).unwrap().borrow_mut().stockpile.get_mut(&ResourceType::Wood).unwrap() = 10.0;
+        *simulation.village(v2).unwrap().borrow_mut().stockpile.get_mut(&ResourceType::Food).unwrap() = 3.0;
+        (v1, v2)
+    }
+
+    #[test]
+    fn exchange_barter_goods_by_caravan() {
+        let mut simulation = Simulation::new();
+        let (v1, v2) = barter_villages(&mut simulation);
+        let mut offer = BarterOffer::new(v2, (ResourceType::Wood, 10), (ResourceType::Food, 3));
+        offer.from = v1;
+
+        assert!(simulation.exchange_barter(&offer));
+        assert_eq!(0.0, *simulation.village(v1).unwrap().borrow().stockpile.get(&ResourceType::Wood).unwrap());
+        assert_eq!(3.0, simulation.village(v1).unwrap().borrow().inbound(ResourceType::Food));
+        assert_eq!(10.0, simulation.village(v2).unwrap().borrow().inbound(ResourceType::Wood));
+        assert_eq!(2, simulation.logistics.caravans().len());
+    }
+
+    #[test]
+    fn trading_partners_spread_technologies() {
+        let mut simulation = Simulation::new();
+        let (v1, v2) = barter_villages(&mut simulation);
+        {
+            let mut teacher = simulation.village(v2).unwrap().borrow_mut();
+            *teacher.stockpile.get_mut(&ResourceType::Gold).unwrap() = 20.0;
+            teacher.start_research(technology::Technology::Forestry).unwrap();
+            for _ in 0..30 {
+                let w = teacher.create_worker(1);
+                teacher.assign_researcher(w).unwrap();
+            }
+            teacher.simulate();
+            assert!(teacher.has_technology(technology::Technology::Forestry));
+            *teacher.stockpile.get_mut(&ResourceType::Food).unwrap() = 3.0;
+
+            let mut learner = simulation.village(v1).unwrap().borrow_mut();
+            *learner.stockpile.get_mut(&ResourceType::Gold).unwrap() = 20.0;
+            learner.start_research(technology::Technology::Forestry).unwrap();
+        }
+        let mut offer = BarterOffer::new(v2, (ResourceType::Wood, 10), (ResourceType::Food, 3));
+        offer.from = v1;
+
+        simulation.spread_technologies();
+        assert_eq!(0, simulation.village(v1).unwrap().borrow().research().unwrap().progress);
+
+        assert!(simulation.exchange_barter(&offer));
+        simulation.spread_technologies();
+
+        assert_eq!(technology::DIFFUSION_PROGRESS, simulation.village(v1).unwrap().borrow().research().unwrap().progress);
+    }
+
+    #[test]
+    fn exchange_barter_missing_goods() {
+        let mut simulation = Simulation::new();
+        let (v1, v2) = barter_villages(&mut simulation);
+        let mut offer = BarterOffer::new(v2, (ResourceType::Wood, 10), (ResourceType::Food, 4));
+        offer.from = v1;
+
+        assert!(!simulation.exchange_barter(&offer));
+        assert_eq!(10.0, *simulation.village(v1).unwrap().borrow().stockpile.get(&ResourceType::Wood).unwrap());
+    }
+
+    #[test]
+    fn simulate_barter_mode_skips_central_market() {
+        let mut simulation = Simulation::new();
+        simulation.market_mode = MarketMode::Barter;
+        let (v1, v2) = barter_villages(&mut simulation);
+        simulation.set_strategy(v1, Box::new(WoodForFood { partner: v2 })).unwrap();
+        simulation.set_strategy(v2, Box::new(AcceptBarters)).unwrap();
+        for &village_id in [v1, v2].iter() {
+            let mut village = simulation.village(village_id).unwrap().borrow_mut();
+            village.decay_rates.insert(ResourceType::Food, 0.0);
+            village.decay_rates.insert(ResourceType::Wood, 0.0);
+        }
+
+        simulation.simulate();
+
+        assert_eq!(1, simulation.negotiations().len());
+        assert_eq!(NegotiationOutcome::Accepted, simulation.negotiations()[0].outcome);
+        assert_eq!(0.0, *simulation.village(v1).unwrap().borrow().stockpile.get(&ResourceType::Wood).unwrap());
+        assert_eq!(0.0, *simulation.village(v2).unwrap().borrow().stockpile.get(&ResourceType::Food).unwrap());
+        assert_eq!(0, simulation.clearing_rounds().len());
+        assert_eq!(PriceDirection::Equilibrium, *simulation.price_directions.get(&ResourceType::Food).unwrap());
+    }
+
+    fn forward_villages(simulation: &mut Simulation) -> (VillageId, VillageId, ContractId) {
+        let buyer = simulation.add_village(default_village());
+        let seller = simulation.add_village(default_village());
+        let contract_id = simulation.forwards.open(buyer, seller, ResourceType::Food, 10, 4, 2);
+        (buyer, seller, contract_id)
+    }
+
+    #[test]
+    fn open_forward_needs_seller_acceptance() {
+        let mut simulation = Simulation::new();
+        let buyer = simulation.add_village(default_village());
+        let seller = simulation.add_village(default_village());
+
+        assert!(simulation.open_forward(buyer, &ForwardRequest::new(buyer, ResourceType::Food, 10, 4, 2)).is_err());
+        assert!(simulation.open_forward(buyer, &ForwardRequest::new(seller, ResourceType::Food, 10, 4, 2)).is_err());
+        assert_eq!(0, simulation.forwards.contracts().len());
+    }
+
+    #[test]
+    fn settle_forwards_at_maturity() {
+        let mut simulation = Simulation::new();
+        let (buyer, seller, contract_id) = forward_villages(&mut simulation);
+        *simulation.village(buyer).unwrap().borrow_mut().stockpile.get_mut(&ResourceType::Gold).unwrap() = 40.0;
+        *simulation.village(seller).unwrap().borrow_mut().stockpile.get_mut(&ResourceType::Food).unwrap() = 10.0;
+
+        simulation.tick = 1;
+        simulation.settle_forwards();
+        assert_eq!(ContractStatus::Open, simulation.forwards.contract(contract_id).unwrap().status);
+
+        simulation.tick = 2;
+        simulation.settle_forwards();
+        assert_eq!(ContractStatus::Delivered, simulation.forwards.contract(contract_id).unwrap().status);
+        assert_eq!(0.0, *simulation.village(buyer).unwrap().borrow().stockpile.get(&ResourceType::Gold).unwrap());
+        assert_eq!(10.0, simulation.village(buyer).unwrap().borrow().inbound(ResourceType::Food));
+        assert_eq!(40.0, *simulation.village(seller).unwrap().borrow().stockpile.get(&ResourceType::Gold).unwrap());
+        assert_eq!(0.0, *simulation.village(seller).unwrap().borrow().stockpile.get(&ResourceType::Food).unwrap());
+    }
+
+    #[test]
+    fn settle_forwards_seller_pays_penalty() {
+        let mut simulation = Simulation::new();
+        let (buyer, seller, contract_id) = forward_villages(&mut simulation);
+        *simulation.village(buyer).unwrap().borrow_mut().stockpile.get_mut(&ResourceType::Gold).unwrap() = 40.0;
+        *simulation.village(seller).unwrap().borrow_mut().stockpile.get_mut(&ResourceType::Gold).unwrap() = 20.0;
+
+        simulation.tick = 2;
+        simulation.settle_forwards();
+
+        assert_eq!(ContractStatus::SellerDefaulted, simulation.forwards.contract(contract_id).unwrap().status);
+        assert_eq!(48.0, *simulation.village(buyer).unwrap().borrow().stockpile.get(&ResourceType::Gold).unwrap());
+        assert_eq!(12.0, *simulation.village(seller).unwrap().borrow().stockpile.get(&ResourceType::Gold).unwrap());
+    }
+
+    #[test]
+    fn settle_forwards_buyer_defaults() {
+        let mut simulation = Simulation::new();
+        let (buyer, seller, contract_id) = forward_villages(&mut simulation);
+        *simulation.village(buyer).unwrap().borrow_mut().stockpile.get_mut(&ResourceType::Gold).unwrap() = 5.0;
+        *simulation.village(seller).unwrap().borrow_mut().stockpile.get_mut(&ResourceType::Food).unwrap() = 10.0;
+
+        simulation.tick = 2;
+        simulation.settle_forwards();
+
+        assert_eq!(ContractStatus::BuyerDefaulted, simulation.forwards.contract(contract_id).unwrap().status);
+        assert_eq!(0.0, *simulation.village(buyer).unwrap().borrow().stockpile.get(&ResourceType::Gold).unwrap());
+        assert_eq!(5.0, *simulation.village(seller).unwrap().borrow().stockpile.get(&ResourceType::Gold).unwrap());
+        assert_eq!(10.0, *simulation.village(seller).unwrap().borrow().stockpile.get(&ResourceType::Food).unwrap());
+    }
+
+    #[test]
+    fn subscribers_receive_events() {
+        let mut simulation = Simulation::new();
+        let v1 = simulation.add_village(default_village());
+        let worker_id = simulation.village(v1).unwrap().borrow_mut().create_worker(1);
+        let events = Rc::new(RefCell::new(vec!()));
+        let received = events.clone();
+        simulation.subscribe(move |e: &Event| received.borrow_mut().push(*e));
+
+        simulation.simulate();
+
+        assert_eq!(vec!(Event::TickStarted { tick: 1 }, Event::WorkerBorn { village_id: v1, worker_id: worker_id }), *events.borrow());
+    }
+
+    #[test]
+    fn subscribers_receive_trade_events() {
+        let mut simulation = Simulation::new();
+        let (v1, v2) = (simulation.add_village(default_village()), simulation.add_village(default_village()));
+        *simulation.village(v1).unwrap().borrow_mut().stockpile.get_mut(&ResourceType::Gold).unwrap() = 100.0;
+        let events = Rc::new(RefCell::new(vec!()));
+        let received = events.clone();
+        simulation.subscribe(move |e: &Event| received.borrow_mut().push(*e));
+        let mut trade_requests = food_trade_requests(&[(v2, 4)], (v1, 10));
+
+        simulation.handle_trades(&mut trade_requests);
+
+        assert_eq!(vec!(
+            Event::RoundStarted { tick: 0, round: 1 },
+            Event::PriceChanged { resource_type: ResourceType::Food, from: 5, to: 6 },
+        ), *events.borrow());
+    }
+
+    #[test]
+    fn simulate_strikes_shocks() {
+        let mut simulation = Simulation::new();
+        let v1 = simulation.add_village(default_village());
+        simulation.shock_rules.push(ShockRule::new(ShockKind::Drought { yield_loss: 0.5 }, 1.0, 2));
+        simulation.shock_rules.push(ShockRule::new(ShockKind::MineCollapse, 1.0, 2));
+        simulation.shock_rules.push(ShockRule::new(ShockKind::GoldDiscovery { amount: 10.0 }, 0.0, 1));
+
+        simulation.simulate();
+
+        assert_eq!(1, simulation.shocks().len());
+        assert_eq!(1, simulation.shocks()[0].tick);
+        assert_eq!(v1, simulation.shocks()[0].village_id);
+        assert_eq!(ShockKind::Drought { yield_loss: 0.5 }, simulation.shocks()[0].shock.kind);
+        assert_eq!(1, simulation.village(v1).unwrap().borrow().shocks().len());
+    }
+
+    #[test]
+    fn simulate_sets_village_dates() {
+        let mut simulation = Simulation::new();
+        simulation.calendar.season_lengths = [1, 1, 1, 1];
+        let v1 = simulation.add_village(default_village());
+
+        for _ in 0..6 {
+            simulation.simulate();
+        }
+
+        assert_eq!(Date { tick: 6, year: 2, season: Season::Summer, day: 1 }, simulation.date());
+        assert_eq!(simulation.date(), simulation.village(v1).unwrap().borrow().date());
+    }
+
+    #[test]
+    fn simulate_indicators() {
+        let mut simulation = Simulation::new();
+        assert!(simulation.indicators().is_none());
+
+        let v1 = simulation.add_village(default_village());
+        {
+            let mut village = simulation.village(v1).unwrap().borrow_mut();
+            let resource_id = village.create_resource(ResourceType::Stone, |x| x * 2.0);
+            let worker_id = village.create_worker(1);
+            village.create_worker(1);
+            village.assign_worker(worker_id, resource_id).unwrap();
+        }
+
+        simulation.simulate();
+
+        let indicators = simulation.indicators().unwrap();
+        assert_eq!(1, indicators.tick);
+        assert_eq!(10.0, indicators.nominal_output);
+        assert_eq!(indicators.nominal_output, indicators.real_output);
+        assert_eq!(0.5, indicators.unemployment);
+    }
+
+    #[test]
+    fn submit_trade_cleared_next_tick() {
+        let mut simulation = Simulation::new();
+        let v1 = simulation.add_village(trading_village(0.0, 100.0));
+        let v2 = simulation.add_village(trading_village(100.0, 0.0));
+        simulation.set_strategy(v2, Box::new(SellFoodAboveFive)).unwrap();
+        let fills = Rc::new(RefCell::new(vec!()));
+        let received = fills.clone();
+        simulation.subscribe(move |e: &Event| if let Event::TradeFilled { buyer, amount, .. } = *e {
+            received.borrow_mut().push((buyer, amount));
+        });
+
+        assert!(simulation.submit_trade(3, TradeRequest::new(TradeType::Buy, 5, ResourceType::Food)).is_err());
+        assert!(simulation.submit_trade(v1, TradeRequest::new(TradeType::Buy, 5, ResourceType::Gold)).is_err());
+        simulation.market_mode = MarketMode::Barter;
+        assert!(simulation.submit_trade(v1, TradeRequest::new(TradeType::Buy, 5, ResourceType::Food)).is_err());
+        simulation.market_mode = MarketMode::Central;
+        simulation.submit_trade(v1, TradeRequest::new(TradeType::Buy, 5, ResourceType::Food)).unwrap();
+
+        simulation.simulate();
+
+        let round = simulation.clearing_rounds().iter().find(|r| r.resource_type == ResourceType::Food).unwrap();
+        assert_eq!((1, 5, 0), (round.tick, round.buys, round.sells));
+        assert_eq!(6, *simulation.prices.get(&ResourceType::Food).unwrap());
+        // the seller only comes in once the price has risen, in the second round
+        assert_eq!(vec!((v1, 5)), *fills.borrow());
+    }
+
+    /// Sells food once its price has risen above the starting price
+    struct SellFoodAboveFive;
+
+    impl Strategy for SellFoodAboveFive {
+        fn trade(&mut self, _village: &Village, prices: &HashMap<ResourceType, u32>) -> Vec<TradeRequest> {
+            if *prices.get(&ResourceType::Food).unwrap() > 5 {
+                vec!(TradeRequest::new(TradeType::Sell, 5, ResourceType::Food))
+            } else {
+                vec!()
+            }
+        }
+    }
+
+    struct BuyFoodOnce {
+        done: bool,
+    }
+
+    impl Strategy for BuyFoodOnce {
+        fn trade(&mut self, _village: &Village, prices: &HashMap<ResourceType, u32>) -> Vec<TradeRequest> {
+            if self.done || *prices.get(&ResourceType::Food).unwrap() != 5 {
+                return vec!();
+            }
+            self.done = true;
+            vec!(TradeRequest::new(TradeType::Buy, 5, ResourceType::Food))
+        }
+    }
+
+    /// Offers its wood for a partner's food and also asks the central market for food
+    struct WoodForFood {
+        partner: VillageId,
+    }
+
+    impl Strategy for WoodForFood {
+        fn trade(&mut self, _village: &Village, _prices: &HashMap<ResourceType, u32>) -> Vec<TradeRequest> {
+            vec!(TradeRequest::new(TradeType::Buy, 5, ResourceType::Food))
+        }
+
+        fn propose_barters(&mut self, _village: &Village) -> Vec<BarterOffer> {
+            vec!(BarterOffer::new(self.partner, (ResourceType::Wood, 10), (ResourceType::Food, 3)))
+        }
+    }
+
+    struct AcceptBarters;
+
+    impl Strategy for AcceptBarters {
+        fn respond_to_barter(&mut self, _village: &Village, _offer: &BarterOffer) -> BarterResponse {
+            BarterResponse::Accept
+        }
+    }
+
+    /// Asks to buy food every round, whatever the price
+    struct AlwaysBuyFood;
+
+    impl Strategy for AlwaysBuyFood {
+        fn trade(&mut self, _village: &Village, _prices: &HashMap<ResourceType, u32>) -> Vec<TradeRequest> {
+            vec!(TradeRequest::new(TradeType::Buy, 5, ResourceType::Food))
+        }
+    }
+
+    #[test]
+    fn simulate_stops_at_clearing_round_limit() {
+        let mut simulation = Simulation::new();
+        let v1 = simulation.add_village(default_village());
+        simulation.set_strategy(v1, Box::new(AlwaysBuyFood)).unwrap();
+        simulation.max_clearing_rounds = 10;
+
+        simulation.simulate();
+        simulation.simulate();
+
+        let food_rounds = |tick| simulation.clearing_rounds().iter().filter(|r| r.tick == tick && r.resource_type == ResourceType::Food).count();
+        assert_eq!((10, 10), (food_rounds(1), food_rounds(2)));
+        assert_eq!(25, *simulation.prices.get(&ResourceType::Food).unwrap());
+    }
+
+    /// Offers food every round and asks for wood nobody sells, so wood keeps the market clearing
+    struct AlwaysSellFood;
+
+    impl Strategy for AlwaysSellFood {
+        fn trade(&mut self, _village: &Village, _prices: &HashMap<ResourceType, u32>) -> Vec<TradeRequest> {
+            vec!(TradeRequest::new(TradeType::Sell, 20, ResourceType::Food), TradeRequest::new(TradeType::Buy, 1, ResourceType::Wood))
+        }
+    }
+
+    #[test]
+    fn simulate_fills_repeated_requests_once_per_tick() {
+        let mut simulation = Simulation::new();
+        let buyer = simulation.add_village(trading_village(0.0, 100.0));
+        let seller = simulation.add_village(trading_village(100.0, 0.0));
+        simulation.set_strategy(buyer, Box::new(AlwaysBuyFood)).unwrap();
+        simulation.set_strategy(seller, Box::new(AlwaysSellFood)).unwrap();
+        let bought = Rc::new(RefCell::new(vec!()));
+        let received = bought.clone();
+        simulation.subscribe(move |e: &Event| match *e {
+            Event::TickStarted { .. } => received.borrow_mut().push(0),
+            Event::TradeFilled { amount, .. } => *received.borrow_mut().last_mut().unwrap() += amount,
+            _ => {},
+        });
+
+        simulation.simulate();
+        simulation.simulate();
+
+        assert_eq!(vec!(5, 5), *bought.borrow());
+    }
+
+    #[test]
+    fn set_strategy_drives_trades() {
+        let mut simulation = Simulation::new();
+        let v1 = simulation.add_village(default_village());
+
+        assert!(simulation.set_strategy(2, Box::new(BuyFoodOnce { done: false })).is_err());
+        simulation.set_strategy(v1, Box::new(BuyFoodOnce { done: false })).unwrap();
+        simulation.simulate();
+
+        let round = simulation.clearing_rounds().iter().find(|r| r.resource_type == ResourceType::Food).unwrap();
+        assert_eq!((1, 5, 0), (round.tick, round.buys, round.sells));
+    }
+
+    #[test]
+    fn initial_resource_prices() {
+        let simulation = Simulation::new();
+
+        assert_eq!(5, *simulation.prices.get(&ResourceType::Food).unwrap());
+    }
+
+    #[test]
+    fn initial_price_directions() {
+        let simulation = Simulation::new();
+
+        assert_eq!(PriceDirection::Equilibrium, *simulation.price_directions.get(&ResourceType::Food).unwrap());
+    }
+
+    #[test]
+    fn handle_trades_basic_sell_and_buy() {
+        let mut simulation = Simulation::new();
+        let mut trade_requests : Vec<TradeRequest> = Vec::new();
+        trade_requests.push(TradeRequest::new(TradeType::Buy, 1, ResourceType::Food));
+        trade_requests.push(TradeRequest::new(TradeType::Sell, 1, ResourceType::Food));
+        simulation.handle_trades(&mut trade_requests);
+
+        assert_eq!(1, trade_requests.first().unwrap().fulfilled_amount);
+        assert_eq!(1, trade_requests.get(1).unwrap().fulfilled_amount);
+        assert_eq!(PriceDirection::Equilibrium, *simulation.price_directions.get(&ResourceType::Food).unwrap());
+    }
+
+    #[test]
+    fn handle_trades_upward_price() {
+        let mut simulation = Simulation::new();
+        let mut trade_requests : Vec<TradeRequest> = Vec::new();
+        trade_requests.push(TradeRequest::new(TradeType::Buy, 1, ResourceType::Food));
+        simulation.handle_trades(&mut trade_requests);
+
+        assert_eq!(0, trade_requests.first().unwrap().fulfilled_amount);
+        assert_eq!(PriceDirection::Upward, *simulation.price_directions.get(&ResourceType::Food).unwrap());
+        assert_eq!(6, *simulation.prices.get(&ResourceType::Food).unwrap());
+    }
+
+    #[test]
+    fn handle_trades_downward_price() {
+        let mut simulation = Simulation::new();
+        let mut trade_requests : Vec<TradeRequest> = Vec::new();
+        trade_requests.push(TradeRequest::new(TradeType::Sell, 1, ResourceType::Food));
+        simulation.handle_trades(&mut trade_requests);
+
+        assert_eq!(0, trade_requests.first().unwrap().fulfilled_amount);
+        assert_eq!(PriceDirection::Downward, *simulation.price_directions.get(&ResourceType::Food).unwrap());
+        assert_eq!(4, *simulation.prices.get(&ResourceType::Food).unwrap());
+    }
+
+    #[test]
+    fn handle_trades_multiple_rounds_downward() {
+        let mut simulation = Simulation::new();
+        let mut trade_requests : Vec<TradeRequest> = Vec::new();
+        trade_requests.push(TradeRequest::new(TradeType::Sell, 1, ResourceType::Food));
+        simulation.handle_trades(&mut trade_requests);
+        simulation.handle_trades(&mut trade_requests);
+        simulation.handle_trades(&mut trade_requests);
+
+        trade_requests.push(TradeRequest::new(TradeType::Buy, 5, ResourceType::Food));
+        simulation.handle_trades(&mut trade_requests);
+
+        assert_eq!(1, trade_requests.first().unwrap().fulfilled_amount);
+        assert_eq!(1, trade_requests.get(1).unwrap().fulfilled_amount);
+        assert_eq!(PriceDirection::Equilibrium, *simulation.price_directions.get(&ResourceType::Food).unwrap());
+        assert_eq!(2, *simulation.prices.get(&ResourceType::Food).unwrap());
+    }
+
+    #[test]
+    fn handle_trades_multiple_rounds_upward() {
+        let mut simulation = Simulation::new();
+        let mut trade_requests : Vec<TradeRequest> = Vec::new();
+        trade_requests.push(TradeRequest::new(TradeType::Buy, 1, ResourceType::Food));
+        simulation.handle_trades(&mut trade_requests);
+        simulation.handle_trades(&mut trade_requests);
+        simulation.handle_trades(&mut trade_requests);
+
+        trade_requests.push(TradeRequest::new(TradeType::Sell, 5, ResourceType::Food));
+        simulation.handle_trades(&mut trade_requests);
+
+        assert_eq!(1, trade_requests.first().unwrap().fulfilled_amount);
+        assert_eq!(1, trade_requests.get(1).unwrap().fulfilled_amount);
+        assert_eq!(PriceDirection::Equilibrium, *simulation.price_directions.get(&ResourceType::Food).unwrap());
+        assert_eq!(8, *simulation.prices.get(&ResourceType::Food).unwrap());
+    }
+
+    #[test]
+    fn handle_trades_price_minimum() {
         let mut simulation = Simulation::new();
         let mut trade_requests : Vec<TradeRequest> = Vec::new();
         trade_requests.push(TradeRequest::new(TradeType::Sell, 2, ResourceType::Food));
@@ -295,12 +1980,34 @@ mod tests {
         simulation.handle_trades(&mut trade_requests);
 
 
-        assert_eq!(1, trade_requests.get(0).unwrap().fulfilled_amount);
+        assert_eq!(1, trade_requests.first().unwrap().fulfilled_amount);
         assert_eq!(1, trade_requests.get(1).unwrap().fulfilled_amount);
         assert_eq!(PriceDirection::Equilibrium, *simulation.price_directions.get(&ResourceType::Food).unwrap());
         assert_eq!(1, *simulation.prices.get(&ResourceType::Food).unwrap());
     }
 
+    #[test]
+    fn handle_trades_logs_clearing_rounds() {
+        let mut simulation = Simulation::new();
+        let mut trade_requests = vec!(
+            TradeRequest::new(TradeType::Buy, 10, ResourceType::Food),
+            TradeRequest::new(TradeType::Sell, 4, ResourceType::Food),
+        );
+
+        simulation.handle_trades(&mut trade_requests);
+        trade_requests[1].request_amount = 12;
+        simulation.handle_trades(&mut trade_requests);
+
+        let rounds: Vec<&ClearingRound> = simulation.clearing_rounds().iter()
+            .filter(|r| r.resource_type == ResourceType::Food)
+            .collect();
+        assert_eq!(2, rounds.len());
+        assert_eq!((1, 5, 10, 4, PriceDirection::Upward, 0), (rounds[0].round, rounds[0].price, rounds[0].buys, rounds[0].sells, rounds[0].direction, rounds[0].filled()));
+        assert_eq!((2, 6, 10, 12, PriceDirection::Equilibrium, 10), (rounds[1].round, rounds[1].price, rounds[1].buys, rounds[1].sells, rounds[1].direction, rounds[1].filled()));
+        assert_eq!(vec!(SchedulePoint { price: 5, demand: 10, supply: 4 }, SchedulePoint { price: 6, demand: 10, supply: 12 }),
+            simulation.supply_demand_schedule(0, ResourceType::Food));
+    }
+
     #[test]
     fn handle_trades_no_requests() {
         let mut simulation = Simulation::new();
@@ -0,0 +1,264 @@
+use village::resource::ResourceType;
+use simulation::recording::TickRecord;
+use simulation::indicators::value;
+use std::collections::HashMap;
+
+/// How the monetary authority sets its interest rate and the fiat it issues
+pub enum PolicyRule {
+    /// Fixed interest rate, issuing the authority's base issuance every tick
+    FixedRate(f64),
+    /// Taylor rule on inflation and output:
+    /// neutral_rate + inflation + inflation_weight * (inflation - target_inflation) + output_weight * output_gap
+    /// Issuance is the base issuance scaled by 1 - inflation_weight * (inflation - target_inflation) - output_weight * output_gap,
+    /// so less fiat is issued while inflation is above target or output above potential
+    Taylor { neutral_rate: f64, target_inflation: f64, inflation_weight: f64, output_weight: f64 },
+}
+
+/// What the monetary authority saw and did at the end of a tick
+pub struct PolicyDecision {
+    pub tick: u32,
+    pub inflation: f64,
+    pub output_gap: f64,
+    pub interest_rate: f64,
+    pub issued: f64,
+}
+
+/// Equally weighted average of the prices of every good, in fiat when the market quotes a price for fiat
+/// and in gold otherwise
+pub fn price_index(prices: &HashMap<ResourceType, u32>) -> f64 {
+    let traded: Vec<f64> = ResourceType::iterator()
+        .filter(|r| !r.is_money())
+        .filter_map(|r| prices.get(r))
+        .map(|p| *p as f64)
+        .collect();
+
+    if traded.is_empty() {
+        return 0.0;
+    }
+
+    let index = traded.iter().sum::<f64>() / traded.len() as f64;
+    match prices.get(&ResourceType::Fiat) {
+        Some(fiat_price) if *fiat_price > 0 => index / *fiat_price as f64,
+        _ => index,
+    }
+}
+
+/// Inflation per tick over the last `window` ticks of the recording, 0 until enough ticks are recorded
+pub fn inflation(recording: &[TickRecord], window: u32) -> f64 {
+    let window = window.max(1) as usize;
+    if recording.len() <= window {
+        return 0.0;
+    }
+
+    let current = price_index(&recording[recording.len() - 1].prices);
+    let previous = price_index(&recording[recording.len() - 1 - window].prices);
+    if previous <= 0.0 {
+        return 0.0;
+    }
+
+    (current / previous).powf(1.0 / window as f64) - 1.0
+}
+
+/// Real output of the villages during a tick, valued at the prices of the first tick recorded
+fn real_output(recording: &[TickRecord], index: usize) -> f64 {
+    recording[index].villages.iter()
+        .map(|v| value(&v.produced, &recording[0].prices))
+        .sum()
+}
+
+/// Real output of the last tick relative to its average over the `window` ticks before it,
+/// used as the gap from potential output. 0 until enough ticks are recorded
+pub fn output_gap(recording: &[TickRecord], window: u32) -> f64 {
+    let window = window.max(1) as usize;
+    if recording.len() <= window {
+        return 0.0;
+    }
+
+    let last = recording.len() - 1;
+    let potential = (last - window..last).map(|i| real_output(recording, i)).sum::<f64>() / window as f64;
+    if potential <= 0.0 {
+        return 0.0;
+    }
+
+    real_output(recording, last) / potential - 1.0
+}
+
+/// Optional central bank that sets the central lender's interest rate and issues fiat into the villages' stockpiles
+pub struct MonetaryAuthority {
+    pub policy_rule: PolicyRule,
+    /// Fiat issued each tick with inflation on target and output at potential, shared equally between the villages
+    pub issuance_per_tick: f64,
+    /// Ticks of history inflation and the output gap are measured over
+    pub inflation_window: u32,
+
+    total_issued: f64,
+    decisions: Vec<PolicyDecision>,
+}
+
+impl MonetaryAuthority {
+    pub fn new(policy_rule: PolicyRule, issuance_per_tick: f64, inflation_window: u32) -> MonetaryAuthority {
+        MonetaryAuthority {
+            policy_rule: policy_rule,
+            issuance_per_tick: issuance_per_tick,
+            inflation_window: inflation_window,
+            total_issued: 0.0,
+            decisions: vec!(),
+        }
+    }
+
+    /// Interest rate the policy rule sets for the given inflation and output gap, never below zero
+    pub fn interest_rate(&self, inflation: f64, output_gap: f64) -> f64 {
+        let interest_rate = match self.policy_rule {
+            PolicyRule::FixedRate(interest_rate) => interest_rate,
+            PolicyRule::Taylor { neutral_rate, target_inflation, inflation_weight, output_weight } => {
+                neutral_rate + inflation + inflation_weight * (inflation - target_inflation) + output_weight * output_gap
+            },
+        };
+
+        interest_rate.max(0.0)
+    }
+
+    /// Fiat the policy rule issues for the given inflation and output gap, never below zero
+    pub fn issuance(&self, inflation: f64, output_gap: f64) -> f64 {
+        let scale = match self.policy_rule {
+            PolicyRule::FixedRate(_) => 1.0,
+            PolicyRule::Taylor { target_inflation, inflation_weight, output_weight, .. } => {
+                1.0 - inflation_weight * (inflation - target_inflation) - output_weight * output_gap
+            },
+        };
+
+        (self.issuance_per_tick * scale).max(0.0)
+    }
+
+    /// Measures inflation and the output gap from the recording and decides on the interest rate and fiat issued this tick
+    pub fn decide(&mut self, recording: &[TickRecord]) -> &PolicyDecision {
+        let inflation = inflation(recording, self.inflation_window);
+        let output_gap = output_gap(recording, self.inflation_window);
+        let interest_rate = self.interest_rate(inflation, output_gap);
+        let issued = self.issuance(inflation, output_gap);
+        self.total_issued += issued;
+
+        self.decisions.push(PolicyDecision {
+            tick: recording.last().map(|r| r.tick).unwrap_or(0),
+            inflation: inflation,
+            output_gap: output_gap,
+            interest_rate: interest_rate,
+            issued: issued,
+        });
+
+        self.decisions.last().unwrap()
+    }
+
+    pub fn decisions(&self) -> &Vec<PolicyDecision> {
+        &self.decisions
+    }
+
+    /// Fiat issued over the whole run
+    pub fn total_issued(&self) -> f64 {
+        self.total_issued
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use simulation::monetary_authority::*;
+    use simulation::recording::VillageRecord;
+
+    fn record(tick: u32, food: u32, wood: u32, stone: u32) -> TickRecord {
+        let mut prices = HashMap::new();
+        prices.insert(ResourceType::Food, food);
+        prices.insert(ResourceType::Wood, wood);
+        prices.insert(ResourceType::Stone, stone);
+
+        TickRecord {
+            tick: tick,
+            prices: prices,
+            villages: vec!(),
+            total_debt: 0.0,
+            defaulted_loans: 0,
+            trade_volumes: HashMap::new(),
+        }
+    }
+
+    fn with_output(mut record: TickRecord, food_produced: f64) -> TickRecord {
+        record.villages.push(VillageRecord {
+            village_id: 1,
+            stockpile: HashMap::new(),
+            produced: vec!((ResourceType::Food, food_produced)).into_iter().collect(),
+            worker_count: 1,
+            idle_worker_count: 0,
+            worker_savings: vec!(0.0),
+            technologies: vec!(),
+            research: None,
+            debt: 0.0,
+            credit: 0.0,
+            treasury: HashMap::new(),
+            tax_revenue: HashMap::new(),
+            public_spending: HashMap::new(),
+        });
+        record
+    }
+
+    #[test]
+    fn price_index_average() {
+        assert_eq!(4.0, price_index(&record(1, 2, 4, 6).prices));
+    }
+
+    #[test]
+    fn inflation_over_window() {
+        let recording = vec!(record(1, 4, 4, 4), record(2, 5, 5, 5), record(3, 4, 4, 4));
+
+        assert_eq!(0.0, inflation(&recording[..1], 1));
+        assert_eq!(0.25, inflation(&recording[..2], 1));
+        assert_eq!(0.0, inflation(&recording, 2));
+    }
+
+    #[test]
+    fn output_gap_against_average() {
+        let recording = vec!(with_output(record(1, 4, 4, 4), 10.0), with_output(record(2, 8, 4, 4), 30.0), with_output(record(3, 8, 4, 4), 10.0));
+
+        assert_eq!(0.0, output_gap(&recording[..2], 2));
+        assert_eq!(2.0, output_gap(&recording[..2], 1));
+        assert_eq!(-0.5, output_gap(&recording, 2));
+    }
+
+    #[test]
+    fn taylor_rule() {
+        let authority = MonetaryAuthority::new(PolicyRule::Taylor { neutral_rate: 0.01, target_inflation: 0.02, inflation_weight: 0.5, output_weight: 0.5 }, 0.0, 1);
+
+        assert!((authority.interest_rate(0.04, 0.0) - 0.06).abs() < 1e-9);
+        assert!((authority.interest_rate(0.04, 0.1) - 0.11).abs() < 1e-9);
+        assert_eq!(0.0, authority.interest_rate(-0.5, 0.0));
+    }
+
+    #[test]
+    fn price_index_in_fiat() {
+        let mut prices = record(1, 2, 4, 6).prices;
+        prices.insert(ResourceType::Fiat, 2);
+
+        assert_eq!(2.0, price_index(&prices));
+    }
+
+    #[test]
+    fn taylor_rule_issuance() {
+        let authority = MonetaryAuthority::new(PolicyRule::Taylor { neutral_rate: 0.01, target_inflation: 0.02, inflation_weight: 0.5, output_weight: 0.5 }, 10.0, 1);
+
+        assert!((authority.issuance(0.02, 0.0) - 10.0).abs() < 1e-9);
+        assert!((authority.issuance(0.22, 0.0) - 9.0).abs() < 1e-9);
+        assert!((authority.issuance(0.02, -0.2) - 11.0).abs() < 1e-9);
+        assert_eq!(0.0, authority.issuance(4.0, 0.0));
+    }
+
+    #[test]
+    fn decide_records_decision() {
+        let mut authority = MonetaryAuthority::new(PolicyRule::FixedRate(0.03), 2.0, 1);
+        let recording = vec!(record(1, 4, 4, 4), record(2, 5, 5, 5));
+
+        authority.decide(&recording);
+
+        assert_eq!(1, authority.decisions().len());
+        assert_eq!(0.25, authority.decisions()[0].inflation);
+        assert_eq!(0.03, authority.decisions()[0].interest_rate);
+        assert_eq!(2.0, authority.total_issued());
+    }
+}
@@ -0,0 +1,186 @@
+use simulation::PriceDirection;
+use village::VillageId;
+use village::resource::ResourceType;
+use village_mind::trade_request::*;
+
+/// Most clearing rounds the central market runs in a tick before giving up on equilibrium
+pub const DEFAULT_MAX_CLEARING_ROUNDS: u32 = 50;
+
+/// Trade request as it was when a clearing round ended
+pub struct RoundOrder {
+    pub village_id: VillageId,
+    pub trade_type: TradeType,
+    pub request_amount: u32,
+    /// Amount filled, once the round is settled this is what trade policies, the sellers' stock and the buyers' gold allowed
+    pub fulfilled_amount: u32,
+}
+
+/// Goods a village bought from another during a clearing round
+pub struct RoundFill {
+    pub buyer: VillageId,
+    pub seller: VillageId,
+    pub amount: u32,
+    pub price: u32,
+}
+
+/// One pass of `Simulation::handle_trades` over the trade requests for a resource
+pub struct ClearingRound {
+    pub tick: u32,
+    /// Round of trading within the tick, starting at 1
+    pub round: u32,
+    pub resource_type: ResourceType,
+    /// Price the trade requests were made at
+    pub price: u32,
+    /// Total amount requested to buy at the price
+    pub buys: u32,
+    /// Total amount requested to sell at the price
+    pub sells: u32,
+    /// Direction of the price after the round
+    pub direction: PriceDirection,
+    pub orders: Vec<RoundOrder>,
+    /// Fills settled between villages, empty until the round is settled
+    pub fills: Vec<RoundFill>,
+}
+
+impl ClearingRound {
+    pub fn new(tick: u32, round: u32, resource_type: ResourceType, price: u32, direction: PriceDirection, trade_requests: &[TradeRequest]) -> ClearingRound {
+        let orders = orders(resource_type, trade_requests);
+        let total = |trade_type: TradeType| orders.iter()
+            .filter(|o| o.trade_type == trade_type)
+            .map(|o| o.request_amount)
+            .sum();
+
+        ClearingRound {
+            tick: tick,
+            round: round,
+            resource_type: resource_type,
+            price: price,
+            buys: total(TradeType::Buy),
+            sells: total(TradeType::Sell),
+            direction: direction,
+            orders: orders,
+            fills: vec!(),
+        }
+    }
+
+    /// Replaces the amounts cleared at the price with the amounts settled between villages
+    pub fn settle(&mut self, trade_requests: &[TradeRequest], fills: Vec<RoundFill>) {
+        self.orders = orders(self.resource_type, trade_requests);
+        self.fills = fills;
+    }
+
+    /// Total amount cleared in the round
+    pub fn filled(&self) -> u32 {
+        self.orders.iter()
+            .filter(|o| o.trade_type == TradeType::Buy)
+            .map(|o| o.fulfilled_amount)
+            .sum()
+    }
+}
+
+fn orders(resource_type: ResourceType, trade_requests: &[TradeRequest]) -> Vec<RoundOrder> {
+    trade_requests.iter()
+        .filter(|t| t.resource_type == resource_type)
+        .map(|t| RoundOrder {
+            village_id: t.village_id,
+            trade_type: t.trade_type,
+            request_amount: t.request_amount,
+            fulfilled_amount: t.fulfilled_amount,
+        })
+        .collect()
+}
+
+/// Amounts demanded and supplied at one price
+#[derive(Copy, Clone, PartialEq, Eq, Debug)]
+pub struct SchedulePoint {
+    pub price: u32,
+    pub demand: u32,
+    pub supply: u32,
+}
+
+/// Supply and demand schedule of a resource during a tick, made of every price tried, lowest price first
+/// When a price was tried more than once, the latest round is used
+pub fn schedule(rounds: &[ClearingRound], tick: u32, resource_type: ResourceType) -> Vec<SchedulePoint> {
+    let mut points: Vec<SchedulePoint> = vec!();
+
+    for round in rounds.iter().filter(|r| r.tick == tick && r.resource_type == resource_type) {
+        points.retain(|p| p.price != round.price);
+        points.push(SchedulePoint {
+            price: round.price,
+            demand: round.buys,
+            supply: round.sells,
+        });
+    }
+
+    points.sort_by_key(|p| p.price);
+    points
+}
+
+/// Supply and demand schedules of every resource and tick in the rounds, as CSV for plotting
+pub fn schedules_csv(rounds: &[ClearingRound]) -> String {
+    let mut csv = String::from("tick,resource,price,demand,supply\n");
+    let mut keys: Vec<(u32, ResourceType)> = vec!();
+    for round in rounds.iter() {
+        if !keys.contains(&(round.tick, round.resource_type)) {
+            keys.push((round.tick, round.resource_type));
+        }
+    }
+
+    for (tick, resource_type) in keys {
+        for point in schedule(rounds, tick, resource_type) {
+            csv.push_str(&format!("{},{:?},{},{},{}\n", tick, resource_type, point.price, point.demand, point.supply));
+        }
+    }
+    csv
+}
+
+/// Every clearing round as CSV, one line per round
+pub fn rounds_csv(rounds: &[ClearingRound]) -> String {
+    let mut csv = String::from("tick,round,resource,price,buys,sells,direction,filled\n");
+    for r in rounds.iter() {
+        csv.push_str(&format!("{},{},{:?},{},{},{},{:?},{}\n", r.tick, r.round, r.resource_type, r.price, r.buys, r.sells, r.direction, r.filled()));
+    }
+    csv
+}
+
+#[cfg(test)]
+mod tests {
+    use simulation::price_formation::*;
+
+    fn round(tick: u32, round: u32, price: u32, buys: u32, sells: u32) -> ClearingRound {
+        let trade_requests = vec!(
+            TradeRequest::new(TradeType::Buy, buys, ResourceType::Food),
+            TradeRequest::new(TradeType::Sell, sells, ResourceType::Food),
+            TradeRequest::new(TradeType::Sell, 7, ResourceType::Wood),
+        );
+        ClearingRound::new(tick, round, ResourceType::Food, price, PriceDirection::Upward, &trade_requests)
+    }
+
+    #[test]
+    fn clearing_round_totals() {
+        let round = round(1, 1, 5, 10, 4);
+
+        assert_eq!(10, round.buys);
+        assert_eq!(4, round.sells);
+        assert_eq!(2, round.orders.len());
+        assert_eq!(0, round.filled());
+    }
+
+    #[test]
+    fn schedule_sorted_by_price() {
+        let rounds = vec!(round(1, 1, 5, 10, 4), round(1, 2, 6, 8, 6), round(1, 3, 4, 12, 3), round(2, 1, 5, 1, 1));
+
+        let points = schedule(&rounds, 1, ResourceType::Food);
+
+        assert_eq!(vec!(4, 5, 6), points.iter().map(|p| p.price).collect::<Vec<u32>>());
+        assert_eq!(SchedulePoint { price: 6, demand: 8, supply: 6 }, points[2]);
+        assert_eq!(0, schedule(&rounds, 1, ResourceType::Wood).len());
+    }
+
+    #[test]
+    fn schedules_csv_rows() {
+        let rounds = vec!(round(1, 1, 5, 10, 4), round(1, 2, 6, 8, 6));
+
+        assert_eq!("tick,resource,price,demand,supply\n1,Food,5,10,4\n1,Food,6,8,6\n", schedules_csv(&rounds));
+    }
+}
@@ -0,0 +1,70 @@
+use village::*;
+use village::resource::ResourceType;
+use village::technology::Technology;
+use std::collections::HashMap;
+
+/// State of a Village at the end of a tick
+pub struct VillageRecord {
+    pub village_id: VillageId,
+    pub stockpile: HashMap<ResourceType, f64>,
+    /// Goods collected during the tick
+    pub produced: HashMap<ResourceType, f64>,
+    pub worker_count: u32,
+    pub idle_worker_count: u32,
+    /// Gold each worker holds as savings, in the order of the Village's workers
+    pub worker_savings: Vec<f64>,
+    pub technologies: Vec<Technology>,
+    /// Technology being researched and the share of its research time done so far
+    pub research: Option<(Technology, f64)>,
+    /// Gold owed on active loans
+    pub debt: f64,
+    /// Gold owed to this Village by other villages
+    pub credit: f64,
+    /// Funds held by the village government
+    pub treasury: HashMap<ResourceType, f64>,
+    /// Taxes collected over the whole run
+    pub tax_revenue: HashMap<ResourceType, f64>,
+    /// Treasury funds spent over the whole run
+    pub public_spending: HashMap<ResourceType, f64>,
+}
+
+impl VillageRecord {
+    pub fn new(village_id: VillageId, village: &Village) -> VillageRecord {
+        VillageRecord {
+            village_id: village_id,
+            stockpile: village.stockpile.clone(),
+            produced: ResourceType::iterator().map(|r| (*r, village.produced(*r))).collect(),
+            worker_count: village.workers().len() as u32,
+            idle_worker_count: village.idle_worker_count(),
+            worker_savings: village.workers().iter().map(|w| w.savings).collect(),
+            technologies: village.technologies().clone(),
+            research: village.research().map(|r| {
+                (r.technology, r.progress as f64 / r.technology.research_time() as f64)
+            }),
+            debt: 0.0,
+            credit: 0.0,
+            treasury: ResourceType::iterator().map(|r| (*r, village.treasury.funds(*r))).collect(),
+            tax_revenue: ResourceType::iterator().map(|r| (*r, village.treasury.revenue(*r))).collect(),
+            public_spending: ResourceType::iterator().map(|r| (*r, village.treasury.spending(*r))).collect(),
+        }
+    }
+}
+
+/// State of the Simulation at the end of a tick
+pub struct TickRecord {
+    pub tick: u32,
+    pub prices: HashMap<ResourceType, u32>,
+    pub villages: Vec<VillageRecord>,
+    /// Gold owed on all active loans
+    pub total_debt: f64,
+    /// Loans defaulted on over the whole run
+    pub defaulted_loans: u32,
+    /// Goods of each resource sent between villages during the tick
+    pub trade_volumes: HashMap<ResourceType, u32>,
+}
+
+impl TickRecord {
+    pub fn village(&self, village_id: VillageId) -> Option<&VillageRecord> {
+        self.villages.iter().find(|v| v.village_id == village_id)
+    }
+}
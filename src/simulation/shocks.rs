@@ -0,0 +1,27 @@
+use village::VillageId;
+use village::shock::*;
+
+/// Chance of a shock striking each Village every tick, and how long it lasts when it does
+#[derive(Copy, Clone, PartialEq, Debug)]
+pub struct ShockRule {
+    pub kind: ShockKind,
+    pub probability: f64,
+    pub duration: u32,
+}
+
+impl ShockRule {
+    pub fn new(kind: ShockKind, probability: f64, duration: u32) -> ShockRule {
+        ShockRule {
+            kind: kind,
+            probability: probability,
+            duration: duration,
+        }
+    }
+}
+
+/// Shock that struck a Village
+pub struct ShockRecord {
+    pub tick: u32,
+    pub village_id: VillageId,
+    pub shock: ActiveShock,
+}
@@ -0,0 +1,226 @@
+use village::VillageId;
+use village::resource::ResourceType;
+use village::trade_policy::TradePolicy;
+use village_mind::trade_request::*;
+use std::collections::HashMap;
+
+/// Amount of a resource a buying request received from a selling request
+pub struct Fill {
+    /// Index of the buy request in the matched trade requests
+    pub buy_index: usize,
+    /// Index of the sell request in the matched trade requests
+    pub sell_index: usize,
+    pub buyer: VillageId,
+    pub seller: VillageId,
+    pub resource_type: ResourceType,
+    pub amount: u32,
+}
+
+#[derive(Copy, Clone, PartialEq, Eq, Hash, Debug)]
+pub enum PolicyKind {
+    Tariff,
+    Quota,
+    Embargo,
+}
+
+/// Change in the amount traded between two villages caused by one kind of trade policy
+pub struct TradeDiversion {
+    pub tick: u32,
+    pub policy: PolicyKind,
+    pub buyer: VillageId,
+    pub seller: VillageId,
+    pub resource_type: ResourceType,
+    /// Amount traded with the policies in place minus the amount traded without this kind of policy
+    pub volume_change: i64,
+}
+
+/// Amount of each resource a buyer has imported from a seller during the current tick
+pub type QuotaUsage = HashMap<(VillageId, VillageId, ResourceType), u32>;
+
+/// Amount of each resource each village has bought or sold during the current tick
+pub type TickFills = HashMap<(VillageId, TradeType, ResourceType), u32>;
+
+/// Takes the amounts a village already traded this tick off its trade requests, so a request made again in a later
+/// clearing round only tops up what is still unfilled. Requests left with nothing to trade are removed
+pub fn remove_filled(trade_requests: &mut Vec<TradeRequest>, tick_fills: &TickFills) {
+    let mut filled = tick_fills.clone();
+
+    for trade_request in trade_requests.iter_mut() {
+        if let Some(amount) = filled.get_mut(&(trade_request.village_id, trade_request.trade_type, trade_request.resource_type)) {
+            let taken = (*amount).min(trade_request.request_amount);
+            trade_request.request_amount -= taken;
+            *amount -= taken;
+        }
+    }
+    trade_requests.retain(|t| t.request_amount > 0);
+}
+
+/// Adds the fulfilled amounts of the trade requests to the amounts traded this tick
+pub fn add_filled(trade_requests: &[TradeRequest], tick_fills: &mut TickFills) {
+    for trade_request in trade_requests.iter().filter(|t| t.fulfilled_amount > 0) {
+        *tick_fills.entry((trade_request.village_id, trade_request.trade_type, trade_request.resource_type)).or_insert(0) += trade_request.fulfilled_amount;
+    }
+}
+
+/// Pairs the fulfilled amounts of buy requests with those of sell requests from other villages
+/// Buyers prefer sellers they charge the lowest tariff, and no goods move between villages under an embargo
+/// or beyond the buyer's import quota. Policies of the ignored kind are not applied
+pub fn match_trades(trade_requests: &[TradeRequest], policies: &HashMap<VillageId, TradePolicy>, quota_usage: &mut QuotaUsage, ignored: Option<PolicyKind>) -> Vec<Fill> {
+    let no_policy = TradePolicy::new();
+    let policy = |village_id: VillageId| policies.get(&village_id).unwrap_or(&no_policy);
+    let mut remaining: Vec<u32> = trade_requests.iter().map(|t| t.fulfilled_amount).collect();
+    let mut fills = vec!();
+
+    for (buy_index, buy_request) in trade_requests.iter().enumerate().filter(|&(_, t)| t.trade_type == TradeType::Buy) {
+        let buyer = buy_request.village_id;
+        let resource_type = buy_request.resource_type;
+
+        let mut sell_indexes: Vec<usize> = trade_requests.iter().enumerate()
+            .filter(|&(_, t)| t.trade_type == TradeType::Sell && t.resource_type == resource_type && t.village_id != buyer)
+            .map(|(i, _)| i)
+            .collect();
+        if ignored != Some(PolicyKind::Tariff) {
+            // stable sort keeps the request order between equally taxed sellers
+            sell_indexes.sort_by(|a, b| {
+                let a = policy(buyer).tariff(trade_requests[*a].village_id, resource_type);
+                let b = policy(buyer).tariff(trade_requests[*b].village_id, resource_type);
+                a.total_cmp(&b)
+            });
+        }
+
+        for sell_index in sell_indexes {
+            if remaining[buy_index] == 0 {
+                break;
+            }
+
+            let seller = trade_requests[sell_index].village_id;
+            if ignored != Some(PolicyKind::Embargo) &&
+                (policy(buyer).is_embargoed(seller, resource_type) || policy(seller).is_embargoed(buyer, resource_type)) {
+                continue;
+            }
+
+            let mut amount = remaining[buy_index].min(remaining[sell_index]);
+            let used = quota_usage.entry((buyer, seller, resource_type)).or_insert(0);
+            if ignored != Some(PolicyKind::Quota) {
+                if let Some(quota) = policy(buyer).quota(seller, resource_type) {
+                    amount = amount.min(quota.saturating_sub(*used));
+                }
+            }
+            if amount == 0 {
+                continue;
+            }
+
+            *used += amount;
+            remaining[buy_index] -= amount;
+            remaining[sell_index] -= amount;
+            fills.push(Fill {
+                buy_index: buy_index,
+                sell_index: sell_index,
+                buyer: buyer,
+                seller: seller,
+                resource_type: resource_type,
+                amount: amount,
+            });
+        }
+    }
+
+    fills
+}
+
+/// Total amount traded for each (buyer, seller, resource) in the fills
+pub fn bilateral_volumes(fills: &[Fill]) -> HashMap<(VillageId, VillageId, ResourceType), u32> {
+    let mut volumes = HashMap::new();
+    for fill in fills.iter() {
+        *volumes.entry((fill.buyer, fill.seller, fill.resource_type)).or_insert(0) += fill.amount;
+    }
+    volumes
+}
+
+#[cfg(test)]
+mod tests {
+    use simulation::trade_matching::*;
+
+    fn request(trade_type: TradeType, amount: u32, village_id: VillageId) -> TradeRequest {
+        let mut trade_request = TradeRequest::new(trade_type, amount, ResourceType::Food);
+        trade_request.fulfilled_amount = amount;
+        trade_request.village_id = village_id;
+        trade_request
+    }
+
+    #[test]
+    fn remove_filled_tops_up_requests() {
+        let mut trade_requests = vec!(request(TradeType::Buy, 5, 1), request(TradeType::Buy, 4, 1), request(TradeType::Sell, 5, 1));
+        let mut tick_fills = HashMap::new();
+        add_filled(&[request(TradeType::Buy, 7, 1)], &mut tick_fills);
+
+        remove_filled(&mut trade_requests, &tick_fills);
+
+        assert_eq!(2, trade_requests.len());
+        assert_eq!((TradeType::Buy, 2), (trade_requests[0].trade_type, trade_requests[0].request_amount));
+        assert_eq!((TradeType::Sell, 5), (trade_requests[1].trade_type, trade_requests[1].request_amount));
+    }
+
+    #[test]
+    fn match_without_policies() {
+        let trade_requests = vec!(request(TradeType::Sell, 3, 1), request(TradeType::Sell, 2, 2), request(TradeType::Buy, 5, 3));
+        let fills = match_trades(&trade_requests, &HashMap::new(), &mut HashMap::new(), None);
+
+        assert_eq!(2, fills.len());
+        assert_eq!(3, fills[0].amount);
+        assert_eq!(1, fills[0].seller);
+        assert_eq!(2, fills[1].amount);
+    }
+
+    #[test]
+    fn match_prefers_untaxed_sellers() {
+        let trade_requests = vec!(request(TradeType::Sell, 3, 1), request(TradeType::Sell, 3, 2), request(TradeType::Buy, 3, 3));
+        let mut policy = TradePolicy::new();
+        policy.set_tariff(1, ResourceType::Food, 0.5);
+        let mut policies = HashMap::new();
+        policies.insert(3, policy);
+
+        let fills = match_trades(&trade_requests, &policies, &mut HashMap::new(), None);
+        assert_eq!(2, fills[0].seller);
+
+        let fills = match_trades(&trade_requests, &policies, &mut HashMap::new(), Some(PolicyKind::Tariff));
+        assert_eq!(1, fills[0].seller);
+    }
+
+    #[test]
+    fn match_with_nan_tariff() {
+        let trade_requests = vec!(request(TradeType::Sell, 3, 1), request(TradeType::Sell, 3, 2), request(TradeType::Buy, 3, 3));
+        let mut policy = TradePolicy::new();
+        policy.set_tariff(1, ResourceType::Food, f64::NAN);
+        let mut policies = HashMap::new();
+        policies.insert(3, policy);
+
+        let fills = match_trades(&trade_requests, &policies, &mut HashMap::new(), None);
+        assert_eq!(2, fills[0].seller);
+    }
+
+    #[test]
+    fn match_respects_embargo_both_ways() {
+        let trade_requests = vec!(request(TradeType::Sell, 3, 1), request(TradeType::Buy, 3, 2));
+        let mut policy = TradePolicy::new();
+        policy.embargo_all(2);
+        let mut policies = HashMap::new();
+        policies.insert(1, policy);
+
+        assert_eq!(0, match_trades(&trade_requests, &policies, &mut HashMap::new(), None).len());
+        assert_eq!(1, match_trades(&trade_requests, &policies, &mut HashMap::new(), Some(PolicyKind::Embargo)).len());
+    }
+
+    #[test]
+    fn match_respects_quota_across_calls() {
+        let trade_requests = vec!(request(TradeType::Sell, 3, 1), request(TradeType::Buy, 3, 2));
+        let mut policy = TradePolicy::new();
+        policy.set_quota(1, ResourceType::Food, 4);
+        let mut policies = HashMap::new();
+        policies.insert(2, policy);
+        let mut quota_usage = HashMap::new();
+
+        assert_eq!(3, match_trades(&trade_requests, &policies, &mut quota_usage, None)[0].amount);
+        assert_eq!(1, match_trades(&trade_requests, &policies, &mut quota_usage, None)[0].amount);
+        assert_eq!(0, match_trades(&trade_requests, &policies, &mut quota_usage, None).len());
+    }
+}
@@ -2,6 +2,7 @@ use village::*;
 use village_mind::*;
 
 pub struct VillageManager {
+    pub village_id: VillageId,
     pub village: VillageRef,
     pub village_mind: VillageMind,
 }
\ No newline at end of file
@@ -0,0 +1,125 @@
+use village::*;
+use village::resource::*;
+use village::taxation::TaxPolicy;
+use error::*;
+
+/// Builds a Village with its resources, workers and starting stockpile
+/// Workers may be put to work on a resource given by its index in the order resources were added
+pub struct VillageBuilder {
+    seed: u64,
+    check_for_worker_death: CheckForWorkerDeath,
+    resources: Vec<(ResourceType, CollectResource)>,
+    workers: Vec<(u32, Option<usize>)>,
+    stockpile: Vec<(ResourceType, f64)>,
+    tax_policy: TaxPolicy,
+}
+
+impl Default for VillageBuilder {
+    fn default() -> VillageBuilder {
+        VillageBuilder::new()
+    }
+}
+
+impl VillageBuilder {
+    pub fn new() -> VillageBuilder {
+        VillageBuilder {
+            seed: 0,
+            check_for_worker_death: |_| false,
+            resources: vec!(),
+            workers: vec!(),
+            stockpile: vec!(),
+            tax_policy: TaxPolicy::none(),
+        }
+    }
+
+    /// Seed driving the Village's mortality models and shocks
+    pub fn seed(mut self, seed: u64) -> VillageBuilder {
+        self.seed = seed;
+        self
+    }
+
+    pub fn check_for_worker_death(mut self, check_for_worker_death: CheckForWorkerDeath) -> VillageBuilder {
+        self.check_for_worker_death = check_for_worker_death;
+        self
+    }
+
+    pub fn resource(mut self, resource_type: ResourceType, collect_resource: CollectResource) -> VillageBuilder {
+        self.resources.push((resource_type, collect_resource));
+        self
+    }
+
+    /// Adds an idle worker
+    pub fn worker(mut self, power: u32) -> VillageBuilder {
+        self.workers.push((power, None));
+        self
+    }
+
+    /// Adds a worker working the resource at the given index
+    pub fn worker_on(mut self, power: u32, resource: usize) -> VillageBuilder {
+        self.workers.push((power, Some(resource)));
+        self
+    }
+
+    pub fn stockpile(mut self, resource_type: ResourceType, amount: f64) -> VillageBuilder {
+        self.stockpile.push((resource_type, amount));
+        self
+    }
+
+    pub fn tax_policy(mut self, tax_policy: TaxPolicy) -> VillageBuilder {
+        self.tax_policy = tax_policy;
+        self
+    }
+
+    pub fn build(self) -> Result<Village> {
+        if self.stockpile.iter().any(|&(_, amount)| amount < 0.0 || !amount.is_finite()) {
+            return Err(Error::new("Invalid stockpile amount"));
+        }
+        if self.workers.iter().any(|&(_, resource)| resource.is_some_and(|r| r >= self.resources.len())) {
+            return Err(Error::new("Invalid resource index"));
+        }
+
+        let mut village = Village::with_seed(self.check_for_worker_death, self.seed);
+        let resource_ids: Vec<ResourceId> = self.resources.iter()
+            .map(|&(resource_type, collect_resource)| village.create_resource(resource_type, collect_resource))
+            .collect();
+        for &(power, resource) in self.workers.iter() {
+            let worker_id = village.create_worker(power);
+            if let Some(resource) = resource {
+                village.assign_worker(worker_id, resource_ids[resource])?;
+            }
+        }
+        for &(resource_type, amount) in self.stockpile.iter() {
+            *village.stockpile.get_mut(&resource_type).unwrap() = amount;
+        }
+        village.tax_policy = self.tax_policy;
+
+        Ok(village)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use village::builder::*;
+
+    #[test]
+    fn build_village() {
+        let village = VillageBuilder::new()
+            .resource(ResourceType::Food, |x| x)
+            .worker_on(2, 0)
+            .worker(1)
+            .stockpile(ResourceType::Gold, 10.0)
+            .build()
+            .unwrap();
+
+        assert_eq!(2, village.workers().len());
+        assert_eq!(village.resources()[0].resource_id, village.workers()[0].assigned_resource);
+        assert_eq!(1, village.idle_worker_count());
+        assert_eq!(10.0, *village.stockpile.get(&ResourceType::Gold).unwrap());
+    }
+
+    #[test]
+    fn build_invalid_village() {
+        assert_eq!("Invalid resource index", VillageBuilder::new().worker_on(1, 0).build().err().unwrap().message());
+        assert_eq!("Invalid stockpile amount", VillageBuilder::new().stockpile(ResourceType::Food, -1.0).build().err().unwrap().message());
+    }
+}
@@ -0,0 +1,97 @@
+use std::slice::Iter;
+use village::resource::ResourceType;
+use self::BuildingType::*;
+
+#[derive(Copy, Clone, PartialEq, Eq, Hash, Debug)]
+pub enum BuildingType {
+    /// Increases food production
+    Farm,
+    /// Increases stone and gold production
+    MineShaft,
+    /// Increases storage capacity
+    Warehouse,
+    /// Houses more workers
+    House,
+    /// Brings in gold from passing traders
+    MarketStall,
+}
+
+pub const FARM_PRODUCTION_BONUS: f64 = 0.25;
+pub const MINE_SHAFT_PRODUCTION_BONUS: f64 = 0.25;
+pub const WORKERS_PER_HOUSE: u32 = 5;
+pub const MARKET_STALL_GOLD: f64 = 1.0;
+
+impl BuildingType {
+    pub fn iterator() -> Iter<'static, BuildingType> {
+        static BUILDING_TYPES: [BuildingType; 5] = [Farm, MineShaft, Warehouse, House, MarketStall];
+        BUILDING_TYPES.iter()
+    }
+
+    /// Goods taken from the stockpile when construction is queued
+    pub fn cost(&self) -> Vec<(ResourceType, f64)> {
+        match *self {
+            Farm => vec!((ResourceType::Wood, 20.0), (ResourceType::Stone, 5.0)),
+            MineShaft => vec!((ResourceType::Wood, 30.0), (ResourceType::Stone, 10.0)),
+            Warehouse => vec!((ResourceType::Wood, 20.0), (ResourceType::Stone, 20.0)),
+            House => vec!((ResourceType::Wood, 15.0), (ResourceType::Stone, 5.0)),
+            MarketStall => vec!((ResourceType::Wood, 10.0), (ResourceType::Gold, 10.0)),
+        }
+    }
+
+    /// Worker-ticks of building needed to complete construction
+    pub fn construction_time(&self) -> u32 {
+        match *self {
+            Farm => 20,
+            MineShaft => 40,
+            Warehouse => 30,
+            House => 15,
+            MarketStall => 10,
+        }
+    }
+
+    /// Goods a completed building uses each tick, without them the building stands idle
+    pub fn upkeep(&self) -> Vec<(ResourceType, f64)> {
+        match *self {
+            Farm => vec!((ResourceType::Wood, 0.1)),
+            MineShaft => vec!((ResourceType::Wood, 0.2)),
+            Warehouse => vec!(),
+            House => vec!((ResourceType::Wood, 0.1)),
+            MarketStall => vec!(),
+        }
+    }
+
+    /// Extra share of production this building gives resources of the given type
+    pub fn production_bonus(&self, resource_type: ResourceType) -> f64 {
+        match (*self, resource_type) {
+            (Farm, ResourceType::Food) => FARM_PRODUCTION_BONUS,
+            (MineShaft, ResourceType::Stone) | (MineShaft, ResourceType::Gold) => MINE_SHAFT_PRODUCTION_BONUS,
+            _ => 0.0,
+        }
+    }
+}
+
+pub type BuildingId = u32;
+
+pub struct Building {
+    pub building_id: BuildingId,
+    pub building_type: BuildingType,
+    /// Worker-ticks of building done so far
+    pub progress: u32,
+    /// False while under construction or when upkeep could not be paid last tick
+    pub is_active: bool,
+}
+
+impl Building {
+    pub fn new(building_id: BuildingId, building_type: BuildingType) -> Building {
+        Building {
+            building_id: building_id,
+            building_type: building_type,
+            progress: 0,
+            is_active: false,
+        }
+    }
+
+    pub fn is_complete(&self) -> bool {
+        self.progress >= self.building_type.construction_time()
+    }
+}
@@ -0,0 +1,14 @@
+use village::resource::*;
+use village::worker::*;
+use village::mortality::DeathCause;
+
+/// Something that happened inside a Village, kept until taken with `Village::take_events`
+#[derive(Copy, Clone, PartialEq, Debug)]
+pub enum VillageEvent {
+    WorkerBorn { worker_id: WorkerId },
+    WorkerDied { worker_id: WorkerId, cause: DeathCause },
+    /// Resource id is 0 when the worker is idle or given a task other than a resource
+    WorkerReassigned { worker_id: WorkerId, task: Task, resource_id: ResourceId },
+    /// Goods produced by a resource during a tick, before taxes
+    ResourceCollected { resource_id: ResourceId, resource_type: ResourceType, amount: f64 },
+}
@@ -1,41 +1,136 @@
 pub mod resource;
 pub mod worker;
+pub mod mortality;
+pub mod building;
+pub mod technology;
+pub mod recipe;
+pub mod taxation;
+pub mod trade_policy;
+pub mod events;
+pub mod shock;
+pub mod builder;
 
 use village::resource::*;
 use village::worker::*;
+use village::mortality::*;
+use village::building::*;
+use village::technology::*;
+use village::recipe::*;
+use village::taxation::*;
+use village::trade_policy::*;
+use village::events::*;
+use village::shock::*;
+use rng::Rng;
+use calendar::*;
 use std::collections::HashMap;
 use std::cell::RefCell;
 use std::rc::Rc;
 
 pub type VillageRef = Rc<RefCell<Village>>;
+pub type VillageId = u32;
 
 pub type CheckForWorkerDeath = fn(&Worker) -> bool;
 
+pub const DEFAULT_FOOD_RATION: f64 = 1.0;
+pub const DEFAULT_HEATING_WOOD: f64 = 0.5;
+pub const DEFAULT_BASE_STORAGE: f64 = 100.0;
+pub const STORAGE_PER_WAREHOUSE: f64 = 100.0;
+pub const DEFAULT_BASE_HOUSING: u32 = 10;
+
 pub struct Village {
     pub stockpile: HashMap<ResourceType, f64>,
+    /// Food each worker needs on hand per tick to stay healthy
+    pub food_ration: f64,
+    /// Wood each worker needs on hand per tick for heating
+    pub heating_wood: f64,
+    /// Gold offered to each worker per tick
+    pub wage: f64,
+    /// Interest per tick this Village charges on loans to other villages, None when it does not lend
+    pub lending_rate: Option<f64>,
+    pub tax_policy: TaxPolicy,
+    pub trade_policy: TradePolicy,
+    pub treasury: Treasury,
+    /// Share of each stockpiled resource lost every tick
+    pub decay_rates: HashMap<ResourceType, f64>,
+    /// Goods (other than gold) that can be stored without any warehouses
+    pub base_storage: f64,
+    /// Workers that can be housed without any houses
+    pub base_housing: u32,
+    inbound: HashMap<ResourceType, f64>,
+    paid_wage: f64,
+    spoiled: HashMap<ResourceType, f64>,
+    produced: HashMap<ResourceType, f64>,
+    overflowed: HashMap<ResourceType, f64>,
 
     workers: Vec<Worker>,
     worker_id_counter: u32,
     resources: Vec<Resource>,
     resource_id_counter: u32,
+    buildings: Vec<Building>,
+    building_id_counter: u32,
+    technologies: Vec<Technology>,
+    research: Option<Research>,
+    shocks: Vec<ActiveShock>,
+    date: Date,
+    season_multipliers: HashMap<ResourceType, f64>,
 
     check_for_worker_death: CheckForWorkerDeath,
+    mortality_models: Vec<MortalityModel>,
+    deaths: HashMap<DeathCause, u32>,
+    events: Vec<VillageEvent>,
+    rng: Rng,
 }
 
 impl Village {
     pub fn new(check_for_worker_death: CheckForWorkerDeath) -> Village {
+        Village::with_seed(check_for_worker_death, 0)
+    }
+
+    /// Creates a Village whose mortality models are driven by the given seed
+    pub fn with_seed(check_for_worker_death: CheckForWorkerDeath, seed: u64) -> Village {
         let mut village = Village {
             stockpile: HashMap::new(),
+            food_ration: DEFAULT_FOOD_RATION,
+            heating_wood: DEFAULT_HEATING_WOOD,
+            wage: 0.0,
+            lending_rate: None,
+            tax_policy: TaxPolicy::none(),
+            trade_policy: TradePolicy::new(),
+            treasury: Treasury::new(),
+            decay_rates: HashMap::new(),
+            base_storage: DEFAULT_BASE_STORAGE,
+            base_housing: DEFAULT_BASE_HOUSING,
+            inbound: HashMap::new(),
+            paid_wage: 0.0,
+            spoiled: HashMap::new(),
+            produced: HashMap::new(),
+            overflowed: HashMap::new(),
             workers: vec!(),
             worker_id_counter: 0,
             resources: vec!(),
             resource_id_counter: 0,
+            buildings: vec!(),
+            building_id_counter: 0,
+            technologies: vec!(),
+            research: None,
+            shocks: vec!(),
+            date: Date::start(),
+            season_multipliers: HashMap::new(),
             check_for_worker_death: check_for_worker_death,
+            mortality_models: vec!(),
+            deaths: HashMap::new(),
+            events: vec!(),
+            rng: Rng::new(seed),
         };
 
         //add each resource type to the stockpile
         for resource_type in ResourceType::iterator() {
             village.stockpile.insert(*resource_type, 0 as f64);
+            village.inbound.insert(*resource_type, 0 as f64);
+            village.decay_rates.insert(*resource_type, resource_type.decay_rate());
+            village.spoiled.insert(*resource_type, 0 as f64);
+            village.produced.insert(*resource_type, 0 as f64);
+            village.overflowed.insert(*resource_type, 0 as f64);
         }
 
         village
@@ -47,6 +142,28 @@ impl Village {
     pub fn create_worker(&mut self, power: u32) -> WorkerId {
         self.worker_id_counter += 1;
         self.workers.push(Worker::new(self.worker_id_counter, power));
+        self.events.push(VillageEvent::WorkerBorn { worker_id: self.worker_id_counter });
+
+        self.worker_id_counter
+    }
+
+    /// Takes a Worker out of this Village, e.g. when it migrates elsewhere
+    pub fn remove_worker(&mut self, worker_id: WorkerId) -> Option<Worker> {
+        self.workers.iter()
+            .position(|w| w.worker_id == worker_id)
+            .map(|index| self.workers.remove(index))
+    }
+
+    /// Adds a Worker that came from another Village.
+    /// The worker is given a new id from this Village's counter and starts out idle
+    /// Returns the new id of the Worker instance
+    pub fn adopt_worker(&mut self, mut worker: Worker) -> WorkerId {
+        self.worker_id_counter += 1;
+        worker.worker_id = self.worker_id_counter;
+        worker.assigned_resource = 0;
+        worker.task = Task::Resource;
+        worker.work_streak = 0;
+        self.workers.push(worker);
 
         self.worker_id_counter
     }
@@ -70,6 +187,7 @@ impl Village {
     /// Passing in a resource id of value 0 will cause the worker to be ideal
     pub fn assign_worker(&mut self, worker_id: WorkerId, resource_id: ResourceId) -> Result<(), &'static str> {
         if let Some(w) = self.workers.iter_mut().find(|w| w.worker_id == worker_id) {
+            w.task = Task::Resource;
             if resource_id == 0 {
                 w.assigned_resource = 0;
             } else if let Some(r) = self.resources.iter().find(|r| r.resource_id == resource_id) {
@@ -77,6 +195,7 @@ impl Village {
             } else {
                return Err("Invalid Resource ID");
             }
+            self.events.push(VillageEvent::WorkerReassigned { worker_id: worker_id, task: Task::Resource, resource_id: w.assigned_resource });
         }
         else {
             return Err("Invalid Worker ID");
@@ -84,6 +203,293 @@ impl Village {
         Ok(())
     }
 
+    /// Takes a worker off its resource to work on construction projects
+    pub fn assign_builder(&mut self, worker_id: WorkerId) -> Result<(), &'static str> {
+        self.assign_task(worker_id, Task::Construction)
+    }
+
+    /// Takes a worker off its resource to work on research
+    pub fn assign_researcher(&mut self, worker_id: WorkerId) -> Result<(), &'static str> {
+        self.assign_task(worker_id, Task::Research)
+    }
+
+    fn assign_task(&mut self, worker_id: WorkerId, task: Task) -> Result<(), &'static str> {
+        if let Some(w) = self.workers.iter_mut().find(|w| w.worker_id == worker_id) {
+            w.assigned_resource = 0;
+            w.task = task;
+            self.events.push(VillageEvent::WorkerReassigned { worker_id: worker_id, task: task, resource_id: 0 });
+            Ok(())
+        } else {
+            Err("Invalid Worker ID")
+        }
+    }
+
+    /// Adds a completed Building to this Village at no cost, e.g. when setting up a scenario
+    /// Building id values start at 1 and auto increment in subsequent invocations
+    /// Returns the id of the Building instance
+    pub fn create_building(&mut self, building_type: BuildingType) -> BuildingId {
+        self.building_id_counter += 1;
+        let mut building = Building::new(self.building_id_counter, building_type);
+        building.progress = building_type.construction_time();
+        building.is_active = true;
+        self.buildings.push(building);
+
+        self.building_id_counter
+    }
+
+    /// Queues a construction project, paying its cost from the stockpile up front
+    /// Builders work on queued projects in the order they were queued
+    /// Returns the id of the Building instance
+    pub fn queue_building(&mut self, building_type: BuildingType) -> Result<BuildingId, &'static str> {
+        if !self.is_unlocked(building_type) {
+            return Err("Technology not researched");
+        }
+
+        let cost = building_type.cost();
+        if cost.iter().any(|&(r, amount)| *self.stockpile.get(&r).unwrap() < amount) {
+            return Err("Not enough resources");
+        }
+
+        for (resource_type, amount) in cost {
+            *self.stockpile.get_mut(&resource_type).unwrap() -= amount;
+        }
+
+        Ok(self.add_construction_project(building_type))
+    }
+
+    /// Queues a public construction project, paying its cost from the treasury instead of the stockpile
+    /// Returns the id of the Building instance
+    pub fn fund_building(&mut self, building_type: BuildingType) -> Result<BuildingId, &'static str> {
+        if !self.is_unlocked(building_type) {
+            return Err("Technology not researched");
+        }
+
+        let cost = building_type.cost();
+        if cost.iter().any(|&(r, amount)| self.treasury.funds(r) < amount) {
+            return Err("Not enough funds");
+        }
+
+        for (resource_type, amount) in cost {
+            self.treasury.spend(resource_type, amount);
+        }
+
+        Ok(self.add_construction_project(building_type))
+    }
+
+    fn add_construction_project(&mut self, building_type: BuildingType) -> BuildingId {
+        self.building_id_counter += 1;
+        self.buildings.push(Building::new(self.building_id_counter, building_type));
+
+        self.building_id_counter
+    }
+
+    /// Pays the trade tax on a settled trade worth the given amount of gold into the treasury
+    pub fn pay_trade_tax(&mut self, trade_value: f64) {
+        let gold = self.stockpile.get_mut(&ResourceType::Gold).unwrap();
+        let tax = (trade_value * self.tax_policy.trade_rate).min(gold.max(0.0));
+        *gold -= tax;
+        self.treasury.collect(ResourceType::Gold, tax);
+    }
+
+    /// Whether every technology needed to build the building type has been researched
+    pub fn is_unlocked(&self, building_type: BuildingType) -> bool {
+        Technology::iterator()
+            .filter(|t| t.unlocks().contains(&building_type))
+            .all(|t| self.has_technology(*t))
+    }
+
+    /// Whether every technology needed to craft the recipe has been researched
+    pub fn is_recipe_unlocked(&self, recipe: Recipe) -> bool {
+        Technology::iterator()
+            .filter(|t| t.recipes().contains(&recipe))
+            .all(|t| self.has_technology(*t))
+    }
+
+    /// Crafts batches of a recipe, turning its inputs in the stockpile into its outputs right away
+    pub fn craft(&mut self, recipe: Recipe, batches: u32) -> Result<(), &'static str> {
+        if !self.is_recipe_unlocked(recipe) {
+            return Err("Technology not researched");
+        }
+
+        let batches = batches as f64;
+        let inputs = recipe.inputs();
+        if inputs.iter().any(|&(r, amount)| *self.stockpile.get(&r).unwrap() < amount * batches) {
+            return Err("Not enough resources");
+        }
+
+        for (resource_type, amount) in inputs {
+            *self.stockpile.get_mut(&resource_type).unwrap() -= amount * batches;
+        }
+        for (resource_type, amount) in recipe.outputs() {
+            *self.stockpile.get_mut(&resource_type).unwrap() += amount * batches;
+        }
+
+        Ok(())
+    }
+
+    /// Starts researching a technology, paying its gold cost up front
+    /// Researchers work on it each tick until it is unlocked, only one technology is researched at a time
+    pub fn start_research(&mut self, technology: Technology) -> Result<(), &'static str> {
+        if self.has_technology(technology) {
+            return Err("Technology already researched");
+        }
+        if self.research.is_some() {
+            return Err("Already researching");
+        }
+        if technology.prerequisites().iter().any(|t| !self.has_technology(*t)) {
+            return Err("Missing prerequisite technology");
+        }
+
+        let gold = self.stockpile.get_mut(&ResourceType::Gold).unwrap();
+        if *gold < technology.cost() {
+            return Err("Not enough gold");
+        }
+        *gold -= technology.cost();
+
+        self.research = Some(Research {
+            technology: technology,
+            progress: 0,
+        });
+
+        Ok(())
+    }
+
+    pub fn research(&self) -> Option<&Research> {
+        self.research.as_ref()
+    }
+
+    /// Speeds up the current research when a trading partner already knows the technology
+    /// Returns true when the research was sped up
+    pub fn learn_from_partner(&mut self, known: &[Technology]) -> bool {
+        match self.research {
+            Some(ref mut research) if known.contains(&research.technology) => {
+                research.progress = (research.progress + DIFFUSION_PROGRESS).min(research.technology.research_time());
+                true
+            },
+            _ => false,
+        }
+    }
+
+    pub fn technologies(&self) -> &Vec<Technology> {
+        &self.technologies
+    }
+
+    pub fn has_technology(&self, technology: Technology) -> bool {
+        self.technologies.contains(&technology)
+    }
+
+    /// Food each worker needs per tick once technologies are taken into account
+    pub fn food_need(&self) -> f64 {
+        let reduction: f64 = self.technologies.iter().map(|t| t.ration_reduction()).sum();
+        self.food_ration * (1.0 - reduction).max(0.0)
+    }
+
+    pub fn building(&self, building_id: BuildingId) -> Option<&Building> {
+        self.buildings.iter().find(|b| b.building_id == building_id)
+    }
+
+    pub fn buildings(&self) -> &Vec<Building> {
+        &self.buildings
+    }
+
+    /// Buildings still under construction, in the order they will be worked on
+    pub fn construction_queue(&self) -> Vec<&Building> {
+        self.buildings.iter().filter(|b| !b.is_complete()).collect()
+    }
+
+    /// Number of buildings of a type that are complete and had their upkeep paid
+    pub fn active_buildings(&self, building_type: BuildingType) -> u32 {
+        self.buildings.iter()
+            .filter(|b| b.building_type == building_type && b.is_active)
+            .count() as u32
+    }
+
+    /// Multiplier on the production of a resource type from active buildings and technologies
+    pub fn production_multiplier(&self, resource_type: ResourceType) -> f64 {
+        let buildings: f64 = self.buildings.iter()
+            .filter(|b| b.is_active)
+            .map(|b| b.building_type.production_bonus(resource_type))
+            .sum();
+        let technologies: f64 = self.technologies.iter()
+            .map(|t| t.production_bonus(resource_type))
+            .sum();
+        let yield_loss: f64 = self.shocks.iter()
+            .map(|s| s.yield_loss(resource_type))
+            .sum();
+
+        let season = *self.season_multipliers.get(&resource_type).unwrap_or(&1.0);
+
+        (1.0 + buildings + technologies) * (1.0 - yield_loss).max(0.0) * season
+    }
+
+    /// Moves the Village to a new date, with the production multipliers of the season from the calendar
+    pub fn set_date(&mut self, date: Date, calendar: &Calendar) {
+        self.date = date;
+        self.season_multipliers = calendar.season_multipliers(date.season);
+    }
+
+    pub fn date(&self) -> Date {
+        self.date
+    }
+
+    /// Strikes the Village with a shock lasting the given number of ticks
+    /// A mine collapse puts one of the stone or gold resources still producing out of use, picked at random
+    /// A gold discovery adds its gold right away and does not last
+    /// Returns the shock as it affects the Village
+    pub fn start_shock(&mut self, kind: ShockKind, duration: u32) -> Result<ActiveShock, &'static str> {
+        let mut shock = ActiveShock {
+            kind: kind,
+            duration: duration,
+            remaining: duration,
+            resource_id: None,
+        };
+
+        match kind {
+            ShockKind::MineCollapse => {
+                let mines: Vec<ResourceId> = self.resources.iter()
+                    .filter(|r| r.resource_type == ResourceType::Stone || r.resource_type == ResourceType::Gold)
+                    .filter(|r| !self.is_collapsed(r.resource_id))
+                    .map(|r| r.resource_id)
+                    .collect();
+                if mines.is_empty() {
+                    return Err("No mine to collapse");
+                }
+                let index = (self.rng.next_f64() * mines.len() as f64) as usize;
+                shock.resource_id = Some(mines[index.min(mines.len() - 1)]);
+            },
+            ShockKind::GoldDiscovery { amount } => {
+                *self.stockpile.get_mut(&ResourceType::Gold).unwrap() += amount;
+                shock.remaining = 0;
+                return Ok(shock);
+            },
+            _ => {},
+        }
+
+        if duration > 0 {
+            self.shocks.push(shock);
+        }
+        Ok(shock)
+    }
+
+    /// Shocks still affecting the Village
+    pub fn shocks(&self) -> &Vec<ActiveShock> {
+        &self.shocks
+    }
+
+    /// Whether a mine collapse has put the resource out of use
+    pub fn is_collapsed(&self, resource_id: ResourceId) -> bool {
+        self.shocks.iter().any(|s| s.resource_id == Some(resource_id))
+    }
+
+    /// Number of workers this Village can house
+    pub fn housing_capacity(&self) -> u32 {
+        self.base_housing + self.active_buildings(BuildingType::House) * WORKERS_PER_HOUSE
+    }
+
+    pub fn has_free_housing(&self) -> bool {
+        (self.workers.len() as u32) < self.housing_capacity()
+    }
+
     pub fn resource(&self, resource_id: ResourceId) -> Option<&Resource> {
         self.resources.iter().find(|r| r.resource_id == resource_id)
     }
@@ -99,13 +505,30 @@ impl Village {
     }
 
     pub fn idle_worker_count(&self) -> u32 {
-        self.workers.iter().filter(|w| w.assigned_resource == 0).count() as u32
+        self.workers.iter().filter(|w| w.assigned_resource == 0 && w.task == Task::Resource).count() as u32
+    }
+
+    pub fn builder_count(&self) -> u32 {
+        self.workers.iter().filter(|w| w.task == Task::Construction).count() as u32
+    }
+
+    pub fn researcher_count(&self) -> u32 {
+        self.workers.iter().filter(|w| w.task == Task::Research).count() as u32
+    }
+
+    /// Workers on a task that are not resting this tick
+    fn working_count(&self, task: Task) -> u32 {
+        self.workers.iter().filter(|w| w.task == task && !w.is_resting()).count() as u32
     }
 
     pub fn worker(&self, worker_id: WorkerId) -> Option<&Worker> {
         self.workers.iter().find(|w| w.worker_id == worker_id)
     }
 
+    pub fn workers(&self) -> &Vec<Worker> {
+        &self.workers
+    }
+
     pub fn workers_on_resource(&self, resource_id: u32) -> Vec<&Worker> {
         self.workers.iter().filter(|w| w.assigned_resource == resource_id).collect()
     }
@@ -118,37 +541,383 @@ impl Village {
         power
     }
 
+    /// Total power of the workers on a resource, adjusted for their age and skill at that resource type
+    pub fn effective_power_on_resource(&self, resource_id: u32) -> f64 {
+        let resource_type = match self.resource(resource_id) {
+            Some(resource) => resource.resource_type,
+            None => return 0.0,
+        };
+
+        self.workers_on_resource(resource_id).iter()
+            .filter(|w| !w.is_resting())
+            .map(|w| w.effective_power(resource_type))
+            .sum()
+    }
+
+    /// Amount of a resource bought on the market that is still travelling to this Village
+    pub fn inbound(&self, resource_type: ResourceType) -> f64 {
+        *self.inbound.get(&resource_type).unwrap()
+    }
+
+    /// Records goods sent towards this Village, they stay out of the stockpile until received
+    pub fn add_inbound(&mut self, resource_type: ResourceType, amount: f64) {
+        *self.inbound.get_mut(&resource_type).unwrap() += amount;
+    }
+
+    /// Takes goods off the inbound total, adding them to the stockpile unless they were lost on the way
+    pub fn receive_inbound(&mut self, resource_type: ResourceType, amount: f64, lost: bool) {
+        *self.inbound.get_mut(&resource_type).unwrap() -= amount;
+        if !lost {
+            *self.stockpile.get_mut(&resource_type).unwrap() += amount;
+        }
+    }
+
+    /// Takes the events that happened in this Village since they were last taken, oldest first
+    pub fn take_events(&mut self) -> Vec<VillageEvent> {
+        self.events.drain(..).collect()
+    }
+
+    /// Adds a built-in mortality model, checked for every worker each tick after CheckForWorkerDeath
+    pub fn add_mortality_model(&mut self, mortality_model: MortalityModel) {
+        self.mortality_models.push(mortality_model);
+    }
+
+    /// Number of workers that have died from the given cause
+    pub fn deaths(&self, cause: DeathCause) -> u32 {
+        *self.deaths.get(&cause).unwrap_or(&0)
+    }
+
+    pub fn total_deaths(&self) -> u32 {
+        self.deaths.values().sum()
+    }
+
+    /// Average health and morale of the workers, in the range [0, 1]
+    /// Returns 0 for a Village without workers
+    pub fn wellbeing(&self) -> f64 {
+        if self.workers.is_empty() {
+            return 0.0;
+        }
+
+        let total: f64 = self.workers.iter().map(|w| (w.health + w.morale) / 2.0).sum();
+        total / self.workers.len() as f64
+    }
+
+    /// Food of every kind in the stockpile
+    pub fn food_on_hand(&self) -> f64 {
+        self.stockpile.iter()
+            .filter(|&(r, _)| r.is_food())
+            .map(|(_, amount)| amount.max(0.0))
+            .sum()
+    }
+
+    /// Share of each worker's food ration the stockpile covers, counting every food good, in the range [0, 1]
+    pub fn food_security(&self) -> f64 {
+        self.coverage(self.food_on_hand(), self.food_need())
+    }
+
+    /// How evenly the food in the stockpile is spread over the food goods, in the range [0, 1]
+    /// 0 when there is no food or a single kind of food, 1 when there are equal amounts of each
+    pub fn food_variety(&self) -> f64 {
+        let food = self.food_on_hand();
+        let kinds = ResourceType::iterator().filter(|r| r.is_food()).count() as f64;
+        if food <= 0.0 || kinds < 2.0 {
+            return 0.0;
+        }
+
+        let concentration: f64 = ResourceType::iterator()
+            .filter(|r| r.is_food())
+            .map(|r| (self.stockpile.get(r).unwrap().max(0.0) / food).powi(2))
+            .sum();
+        (1.0 - concentration) / (1.0 - 1.0 / kinds)
+    }
+
+    /// Gold each worker actually received last tick, which is less than the wage when the Village ran out of gold
+    pub fn paid_wage(&self) -> f64 {
+        self.paid_wage
+    }
+
+    /// Gold held by the workers of this Village from the wages they were paid
+    pub fn household_savings(&self) -> f64 {
+        self.workers.iter().map(|w| w.savings).sum()
+    }
+
+    /// Total amount of goods, other than gold, this Village can store
+    pub fn storage_capacity(&self) -> f64 {
+        self.base_storage + self.active_buildings(BuildingType::Warehouse) as f64 * STORAGE_PER_WAREHOUSE
+    }
+
+    /// Total amount of goods, other than gold, currently in the stockpile
+    pub fn stored_goods(&self) -> f64 {
+        self.stockpile.iter()
+            .filter(|&(r, _)| !r.is_money())
+            .map(|(_, amount)| amount.max(0.0))
+            .sum()
+    }
+
+    /// Amount of a resource collected during the last tick, before taxes
+    pub fn produced(&self, resource_type: ResourceType) -> f64 {
+        *self.produced.get(&resource_type).unwrap()
+    }
+
+    /// Amount of a resource that has rotted or otherwise decayed in the stockpile over the whole run
+    pub fn spoiled(&self, resource_type: ResourceType) -> f64 {
+        *self.spoiled.get(&resource_type).unwrap()
+    }
+
+    /// Amount of a resource thrown away for lack of storage over the whole run
+    pub fn overflowed(&self, resource_type: ResourceType) -> f64 {
+        *self.overflowed.get(&resource_type).unwrap()
+    }
+
+    fn decay_stockpile(&mut self) {
+        for (resource_type, amount) in self.stockpile.iter_mut() {
+            let spoiled = amount.max(0.0) * *self.decay_rates.get(resource_type).unwrap_or(&0.0);
+            *amount -= spoiled;
+            *self.spoiled.get_mut(resource_type).unwrap() += spoiled;
+        }
+    }
+
+    /// Discards goods beyond the storage capacity, taking an equal share from each resource
+    fn discard_overflow(&mut self) {
+        let stored = self.stored_goods();
+        let capacity = self.storage_capacity().max(0.0);
+        if stored <= capacity {
+            return;
+        }
+
+        let kept_share = capacity / stored;
+        for (resource_type, amount) in self.stockpile.iter_mut().filter(|&(r, _)| !r.is_money()) {
+            if *amount > 0.0 {
+                let lost = *amount * (1.0 - kept_share);
+                *amount -= lost;
+                *self.overflowed.get_mut(resource_type).unwrap() += lost;
+            }
+        }
+    }
+
+    /// Pays upkeep for each completed building, a building whose upkeep cannot be paid is inactive this tick
+    fn maintain_buildings(&mut self) {
+        let stockpile = &mut self.stockpile;
+
+        for building in self.buildings.iter_mut().filter(|b| b.is_complete()) {
+            let upkeep = building.building_type.upkeep();
+            building.is_active = upkeep.iter().all(|&(r, amount)| *stockpile.get(&r).unwrap() >= amount);
+
+            if building.is_active {
+                for (resource_type, amount) in upkeep {
+                    *stockpile.get_mut(&resource_type).unwrap() -= amount;
+                }
+            }
+        }
+    }
+
+    /// Each builder adds a worker-tick to the construction queue, finishing projects in order
+    fn construct_buildings(&mut self) {
+        let mut worker_ticks = self.working_count(Task::Construction);
+
+        for building in self.buildings.iter_mut().filter(|b| !b.is_complete()) {
+            if worker_ticks == 0 {
+                break;
+            }
+
+            let work = worker_ticks.min(building.building_type.construction_time() - building.progress);
+            building.progress += work;
+            worker_ticks -= work;
+        }
+    }
+
+    /// Each researcher adds a worker-tick to the current research, unlocking the technology once done
+    fn conduct_research(&mut self) {
+        let researchers = self.working_count(Task::Research);
+        let unlocked = match self.research {
+            Some(ref mut research) => {
+                research.progress += researchers;
+                research.progress >= research.technology.research_time()
+            },
+            None => false,
+        };
+
+        if unlocked {
+            let research = self.research.take().unwrap();
+            self.technologies.push(research.technology);
+        }
+    }
+
+    fn collect_wealth_tax(&mut self) {
+        let gold = self.stockpile.get_mut(&ResourceType::Gold).unwrap();
+        let tax = gold.max(0.0) * self.tax_policy.wealth_rate;
+        *gold -= tax;
+        self.treasury.collect(ResourceType::Gold, tax);
+    }
+
+    /// Moves food from the treasury to the stockpile to cover the workers' rations, if the tax policy allows it
+    fn provide_food_relief(&mut self) {
+        if !self.tax_policy.food_relief {
+            return;
+        }
+
+        let need = self.food_need() * self.workers.len() as f64;
+        let shortfall = need - self.food_on_hand();
+        let relief = shortfall.min(self.treasury.funds(ResourceType::Food));
+        if relief > 0.0 {
+            *self.stockpile.get_mut(&ResourceType::Food).unwrap() += relief;
+            self.treasury.spend(ResourceType::Food, relief);
+        }
+    }
+
+    /// Share of a per worker need the amount on hand covers, in the range [0, 1]
+    fn coverage(&self, on_hand: f64, need: f64) -> f64 {
+        if need <= 0.0 || self.workers.is_empty() {
+            return 1.0;
+        }
+
+        let per_worker = on_hand / self.workers.len() as f64;
+        (per_worker / need).clamp(0.0, 1.0)
+    }
+
     pub fn simulate(&mut self) {
-        for resource in self.resources.iter() {
-            let power = self.power_on_resource(resource.resource_id);
-            *self.stockpile.get_mut(&resource.resource_type).unwrap() += (resource.collect_resource)(power);
+        // goods stored since last tick decay before new goods come in
+        self.decay_stockpile();
+        self.maintain_buildings();
+
+        for amount in self.produced.values_mut() {
+            *amount = 0.0;
         }
 
+        let shocks = &self.shocks;
+        for resource in self.resources.iter().filter(|r| !shocks.iter().any(|s| s.resource_id == Some(r.resource_id))) {
+            let power = self.effective_power_on_resource(resource.resource_id);
+            let multiplier = self.production_multiplier(resource.resource_type);
+            let produced = (resource.collect_resource)(power) * multiplier;
+            let tax = produced.max(0.0) * self.tax_policy.production_rate;
+            *self.stockpile.get_mut(&resource.resource_type).unwrap() += produced - tax;
+            self.treasury.collect(resource.resource_type, tax);
+            *self.produced.get_mut(&resource.resource_type).unwrap() += produced;
+            self.events.push(VillageEvent::ResourceCollected {
+                resource_id: resource.resource_id,
+                resource_type: resource.resource_type,
+                amount: produced,
+            });
+        }
+
+        *self.stockpile.get_mut(&ResourceType::Gold).unwrap() += self.active_buildings(BuildingType::MarketStall) as f64 * MARKET_STALL_GOLD;
+
+        self.construct_buildings();
+        self.conduct_research();
+
+        // pay wages out of the gold on hand into the workers' savings
+        if !self.workers.is_empty() {
+            let gold = self.stockpile.get_mut(&ResourceType::Gold).unwrap();
+            let paid = (self.wage * self.workers.len() as f64).min(gold.max(0.0));
+            *gold -= paid;
+            self.paid_wage = paid / self.workers.len() as f64;
+            for worker in self.workers.iter_mut() {
+                worker.savings += self.paid_wage;
+            }
+        } else {
+            self.paid_wage = 0.0;
+        }
+
+        self.collect_wealth_tax();
+        self.provide_food_relief();
+
+        let conditions = LivingConditions {
+            food: self.food_security(),
+            variety: self.food_variety(),
+            heating: self.coverage(*self.stockpile.get(&ResourceType::Wood).unwrap(), self.heating_wood),
+        };
+
+        let plague_hazard: f64 = self.shocks.iter().map(|s| s.hazard()).sum();
+        let food_per_worker = if !self.workers.is_empty() {
+            self.food_on_hand() / self.workers.len() as f64
+        } else {
+            0.0
+        };
+
         for worker in self.workers.iter_mut() {
+            let resource_type = self.resources.iter()
+                .find(|r| r.resource_id == worker.assigned_resource)
+                .map(|r| r.resource_type);
+
+            // time spent on a resource builds skill for that resource type
+            if worker.is_resting() {
+                worker.work_streak = 0;
+            } else if let Some(resource_type) = resource_type {
+                worker.gain_experience(resource_type);
+                worker.work_streak += 1;
+            } else if worker.task != Task::Resource {
+                worker.work_streak += 1;
+            } else {
+                worker.work_streak = 0;
+            }
+
+            worker.update_wellbeing(&conditions);
+
             worker.age += 1;
-            worker.is_alive = !(self.check_for_worker_death)(&worker);
+
+            let context = MortalityContext {
+                resource_type: resource_type,
+                food_per_worker: food_per_worker,
+            };
+            let mut cause = None;
+
+            if (self.check_for_worker_death)(worker) {
+                cause = Some(DeathCause::Custom);
+            } else {
+                for mortality_model in self.mortality_models.iter() {
+                    if self.rng.chance(mortality_model.hazard(worker, &context)) {
+                        cause = Some(mortality_model.cause());
+                        break;
+                    }
+                }
+                if cause.is_none() && plague_hazard > 0.0 && self.rng.chance(plague_hazard) {
+                    cause = Some(DeathCause::Plague);
+                }
+            }
+
+            if let Some(cause) = cause {
+                worker.is_alive = false;
+                *self.deaths.entry(cause).or_insert(0) += 1;
+                self.events.push(VillageEvent::WorkerDied { worker_id: worker.worker_id, cause: cause });
+            }
+        }
+
+        self.discard_overflow();
+
+        for shock in self.shocks.iter_mut() {
+            shock.remaining -= 1;
         }
+        self.shocks.retain(|s| s.remaining > 0);
 
-        // remove workers not alive
-        self.workers.retain(|ref w| w.is_alive);
+        // savings of workers not alive go back to the Village, then remove them
+        let estates: f64 = self.workers.iter().filter(|w| !w.is_alive).map(|w| w.savings).sum();
+        *self.stockpile.get_mut(&ResourceType::Gold).unwrap() += estates;
+        self.workers.retain(|w| w.is_alive);
     }
 }
 
 #[cfg(test)]
 mod tests {
     use village::*;
-    use village::worker::*;
 
     fn default_village() -> Village {
-        Village::new(|w: &Worker| false)
+        Village::new(|_w: &Worker| false)
     }
 
     fn default_worker(village: &mut Village) -> WorkerId {
         village.create_worker(1)
     }
 
-    fn default_collect_resource() -> fn(u32) -> f64 {
-        move |x| x as f64
+    fn default_collect_resource() -> fn(f64) -> f64 {
+        move |x| x
+    }
+
+    /// Stocks food, fish and wood, with fish spoiling like food so the diet stays evenly varied
+    fn feed_and_heat(village: &mut Village) {
+        village.decay_rates.insert(ResourceType::Fish, ResourceType::Food.decay_rate());
+        for resource_type in [ResourceType::Food, ResourceType::Fish, ResourceType::Wood].iter() {
+            *village.stockpile.get_mut(resource_type).unwrap() = 10.0;
+        }
     }
 
     #[test]
@@ -167,7 +936,7 @@ mod tests {
         let w1 = default_worker(&mut v);
         let r1 = v.create_resource(ResourceType::Gold, default_collect_resource());
 
-        v.assign_worker(w1, r1);
+        v.assign_worker(w1, r1).unwrap();
 
         assert_eq!(1, v.workers_on_resource(r1).len());
         assert_eq!(0, v.idle_worker_count());
@@ -179,8 +948,8 @@ mod tests {
         let w1 = default_worker(&mut v);
         let r1 = v.create_resource(ResourceType::Gold, default_collect_resource());
 
-        v.assign_worker(w1, r1);
-        v.assign_worker(w1, 0);
+        v.assign_worker(w1, r1).unwrap();
+        v.assign_worker(w1, 0).unwrap();
 
         assert_eq!(0, v.workers_on_resource(r1).len());
         assert_eq!(1, v.idle_worker_count());
@@ -217,8 +986,8 @@ mod tests {
         let w1 = default_worker(&mut v);
         let w2 = default_worker(&mut v);
 
-        v.assign_worker(w1, r1);
-        v.assign_worker(w2, r1);
+        v.assign_worker(w1, r1).unwrap();
+        v.assign_worker(w2, r1).unwrap();
         v.simulate();
 
         assert_eq!(2, *v.stockpile.get(&ResourceType::Wood).unwrap() as u32);
@@ -248,34 +1017,824 @@ mod tests {
     }
 
     #[test]
-    fn simulate_worker_death() {
-        let mut v = Village::new(|w| w.worker_id == 1);
+    fn simulate_worker_gains_skill() {
+        let mut v = default_village();
+        let r1 = v.create_resource(ResourceType::Wood, default_collect_resource());
         let w1 = default_worker(&mut v);
-        let w2 = default_worker(&mut v);
+        v.assign_worker(w1, r1).unwrap();
 
         v.simulate();
 
-        assert!(v.worker(w1).is_none());
-        assert!(v.worker(w2).is_some());
+        assert!(v.worker(w1).unwrap().skill(ResourceType::Wood) > 0.0);
+        assert_eq!(0.0, v.worker(w1).unwrap().skill(ResourceType::Stone));
     }
 
     #[test]
-    fn get_resources_by_type() {
+    fn simulate_idle_worker_gains_no_skill() {
         let mut v = default_village();
-        v.create_resource(ResourceType::Gold, default_collect_resource());
-        v.create_resource(ResourceType::Wood, default_collect_resource());
-        v.create_resource(ResourceType::Wood, default_collect_resource());
+        let w1 = default_worker(&mut v);
 
-        assert_eq!(1, v.resources_of_type(ResourceType::Gold).len());
-        assert_eq!(2, v.resources_of_type(ResourceType::Wood).len());
-        assert_eq!(0, v.resources_of_type(ResourceType::Stone).len());
-        assert_eq!(0, v.resources_of_type(ResourceType::Food).len());
+        v.simulate();
+
+        assert!(v.worker(w1).unwrap().skills.is_empty());
+    }
+
+    #[test]
+    fn simulate_experienced_workers_collect_more() {
+        let mut v = default_village();
+        let r1 = v.create_resource(ResourceType::Wood, default_collect_resource());
+        let w1 = default_worker(&mut v);
+        v.assign_worker(w1, r1).unwrap();
+        feed_and_heat(&mut v);
+
+        v.simulate();
+        let first_tick = *v.stockpile.get(&ResourceType::Wood).unwrap() - 10.0;
+        v.simulate();
+        let second_tick = *v.stockpile.get(&ResourceType::Wood).unwrap() - 10.0 - first_tick;
+
+        assert!(second_tick > first_tick);
+    }
+
+    #[test]
+    fn reassigned_worker_starts_unskilled() {
+        let mut v = default_village();
+        let r1 = v.create_resource(ResourceType::Wood, default_collect_resource());
+        let r2 = v.create_resource(ResourceType::Stone, default_collect_resource());
+        let w1 = default_worker(&mut v);
+        // fed a varied diet and heated, so health and morale leave power unchanged
+        feed_and_heat(&mut v);
+        v.assign_worker(w1, r1).unwrap();
+        v.simulate();
+
+        assert!(v.effective_power_on_resource(r1) > v.worker(w1).unwrap().age_factor());
+
+        v.assign_worker(w1, r2).unwrap();
+
+        assert_eq!(v.worker(w1).unwrap().age_factor(), v.effective_power_on_resource(r2));
+    }
+
+    #[test]
+    fn worker_power_rises_then_falls_with_age() {
+        let mut v = default_village();
+        let w1 = default_worker(&mut v);
+
+        assert_eq!(1.0, v.worker(w1).unwrap().age_factor());
+
+        for _ in 0..PRIME_AGE {
+            v.simulate();
+        }
+        let prime = v.worker(w1).unwrap().age_factor();
+        assert!(prime > 1.0);
+
+        for _ in 0..PRIME_AGE {
+            v.simulate();
+        }
+        assert!(v.worker(w1).unwrap().age_factor() < prime);
+    }
+
+    #[test]
+    fn simulate_wellbeing_kept_with_food_and_heating() {
+        let mut v = default_village();
+        default_worker(&mut v);
+        feed_and_heat(&mut v);
+
+        v.simulate();
+
+        assert_eq!(1.0, v.food_variety());
+        assert_eq!(1.0, v.wellbeing());
+    }
+
+    #[test]
+    fn food_variety_of_stockpile() {
+        let mut v = default_village();
+        assert_eq!(0.0, v.food_variety());
+
+        *v.stockpile.get_mut(&ResourceType::Food).unwrap() = 10.0;
+        assert_eq!(0.0, v.food_variety());
+
+        *v.stockpile.get_mut(&ResourceType::Fish).unwrap() = 10.0;
+        assert_eq!(1.0, v.food_variety());
+
+        *v.stockpile.get_mut(&ResourceType::Fish).unwrap() = 30.0;
+        assert_eq!(0.75, v.food_variety());
+        assert_eq!(40.0, v.food_on_hand());
+    }
+
+    #[test]
+    fn simulate_single_food_lowers_wellbeing() {
+        let mut v = default_village();
+        let w1 = default_worker(&mut v);
+        *v.stockpile.get_mut(&ResourceType::Fish).unwrap() = 10.0;
+        *v.stockpile.get_mut(&ResourceType::Wood).unwrap() = 10.0;
+
+        v.simulate();
+
+        assert_eq!(1.0, v.food_security());
+        assert!(v.worker(w1).unwrap().health < 1.0);
+        assert!(v.worker(w1).unwrap().morale < 1.0);
+    }
+
+    #[test]
+    fn simulate_wellbeing_falls_without_food() {
+        let mut v = default_village();
+        let w1 = default_worker(&mut v);
+        *v.stockpile.get_mut(&ResourceType::Wood).unwrap() = 10.0;
+
+        v.simulate();
+
+        assert!(v.worker(w1).unwrap().health < 1.0);
+        assert!(v.wellbeing() < 1.0);
+    }
+
+    #[test]
+    fn simulate_overwork_lowers_morale() {
+        let mut v = default_village();
+        let r1 = v.create_resource(ResourceType::Stone, default_collect_resource());
+        let w1 = default_worker(&mut v);
+        let w2 = default_worker(&mut v);
+        v.assign_worker(w1, r1).unwrap();
+        feed_and_heat(&mut v);
+        v.decay_rates.insert(ResourceType::Food, 0.0);
+        v.decay_rates.insert(ResourceType::Fish, 0.0);
+        v.decay_rates.insert(ResourceType::Wood, 0.0);
+
+        for _ in 0..OVERWORK_STREAK + 5 {
+            v.simulate();
+        }
+
+        assert!(v.worker(w1).unwrap().is_overworked());
+        assert!(v.worker(w1).unwrap().morale < 1.0);
+        assert_eq!(1.0, v.worker(w2).unwrap().morale);
+    }
+
+    #[test]
+    fn simulate_overworked_worker_rests() {
+        let mut v = default_village();
+        let r1 = v.create_resource(ResourceType::Stone, default_collect_resource());
+        let w1 = default_worker(&mut v);
+        v.assign_worker(w1, r1).unwrap();
+
+        for _ in 0..REST_STREAK {
+            v.simulate();
+        }
+        assert!(v.worker(w1).unwrap().is_resting());
+        assert_eq!(0.0, v.effective_power_on_resource(r1));
+        let stone = *v.stockpile.get(&ResourceType::Stone).unwrap();
+
+        v.simulate();
+
+        assert_eq!(stone, *v.stockpile.get(&ResourceType::Stone).unwrap());
+        assert_eq!(0, v.worker(w1).unwrap().work_streak);
+        assert!(!v.worker(w1).unwrap().is_overworked());
+        assert!(v.effective_power_on_resource(r1) > 0.0);
+    }
+
+    #[test]
+    fn unhealthy_workers_collect_less() {
+        let mut v = default_village();
+        let r1 = v.create_resource(ResourceType::Stone, default_collect_resource());
+        let w1 = default_worker(&mut v);
+        v.assign_worker(w1, r1).unwrap();
+        let healthy = v.effective_power_on_resource(r1);
+
+        v.workers[0].health = 0.5;
+
+        assert!(v.effective_power_on_resource(r1) < healthy);
+    }
+
+    #[test]
+    fn wellbeing_without_workers() {
+        let v = default_village();
+
+        assert_eq!(0.0, v.wellbeing());
+    }
+
+    #[test]
+    fn simulate_worker_death() {
+        let mut v = Village::new(|w| w.worker_id == 1);
+        let w1 = default_worker(&mut v);
+        let w2 = default_worker(&mut v);
+
+        v.simulate();
+
+        assert!(v.worker(w1).is_none());
+        assert!(v.worker(w2).is_some());
+    }
+
+    #[test]
+    fn simulate_worker_death_statistics() {
+        let mut v = Village::new(|w| w.worker_id == 1);
+        default_worker(&mut v);
+        default_worker(&mut v);
+
+        v.simulate();
+
+        assert_eq!(1, v.deaths(DeathCause::Custom));
+        assert_eq!(1, v.total_deaths());
+    }
+
+    #[test]
+    fn simulate_gompertz_old_age() {
+        let mut v = default_village();
+        v.add_mortality_model(MortalityModel::Gompertz { base_hazard: 0.001, growth_rate: 0.1 });
+        let w1 = default_worker(&mut v);
+
+        for _ in 0..200 {
+            v.simulate();
+        }
+
+        assert!(v.worker(w1).is_none());
+        assert_eq!(1, v.deaths(DeathCause::OldAge));
+    }
+
+    #[test]
+    fn simulate_starvation() {
+        let mut fed = default_village();
+        let mut starving = default_village();
+        for v in [&mut fed, &mut starving].iter_mut() {
+            v.add_mortality_model(MortalityModel::Starvation { ration: 1.0, max_hazard: 1.0 });
+            default_worker(v);
+        }
+        *fed.stockpile.get_mut(&ResourceType::Food).unwrap() = 1.0;
+
+        fed.simulate();
+        starving.simulate();
+
+        assert_eq!(0, fed.total_deaths());
+        assert_eq!(1, starving.deaths(DeathCause::Starvation));
+    }
+
+    #[test]
+    fn simulate_accident_only_on_resource_type() {
+        let mut v = default_village();
+        v.add_mortality_model(MortalityModel::Accident { resource_type: ResourceType::Stone, hazard: 1.0 });
+        let r1 = v.create_resource(ResourceType::Stone, default_collect_resource());
+        let r2 = v.create_resource(ResourceType::Wood, default_collect_resource());
+        let w1 = default_worker(&mut v);
+        let w2 = default_worker(&mut v);
+        let w3 = default_worker(&mut v);
+        v.assign_worker(w1, r1).unwrap();
+        v.assign_worker(w2, r2).unwrap();
+
+        v.simulate();
+
+        assert!(v.worker(w1).is_none());
+        assert!(v.worker(w2).is_some());
+        assert!(v.worker(w3).is_some());
+        assert_eq!(1, v.deaths(DeathCause::Accident));
+    }
+
+    #[test]
+    fn simulate_mortality_same_seed_same_deaths() {
+        let mut a = Village::with_seed(|_| false, 9);
+        let mut b = Village::with_seed(|_| false, 9);
+        for v in [&mut a, &mut b].iter_mut() {
+            v.add_mortality_model(MortalityModel::Gompertz { base_hazard: 0.01, growth_rate: 0.05 });
+            for _ in 0..20 {
+                default_worker(v);
+            }
+        }
+
+        for _ in 0..30 {
+            a.simulate();
+            b.simulate();
+            assert_eq!(a.total_deaths(), b.total_deaths());
+        }
+    }
+
+    #[test]
+    fn drought_cuts_food_for_its_duration() {
+        let mut v = default_village();
+        v.decay_rates.insert(ResourceType::Food, 0.0);
+        let resource_id = v.create_resource(ResourceType::Food, |x| x * 10.0);
+        let worker_id = default_worker(&mut v);
+        v.assign_worker(worker_id, resource_id).unwrap();
+        v.start_shock(ShockKind::Drought { yield_loss: 0.5 }, 1).unwrap();
+
+        assert_eq!(0.5, v.production_multiplier(ResourceType::Food));
+        assert_eq!(1.0, v.production_multiplier(ResourceType::Wood));
+
+        v.simulate();
+        assert_eq!(0, v.shocks().len());
+        assert_eq!(1.0, v.production_multiplier(ResourceType::Food));
+    }
+
+    #[test]
+    fn mine_collapse_stops_a_mine() {
+        let mut v = default_village();
+        let food_id = v.create_resource(ResourceType::Food, default_collect_resource());
+        let stone_id = v.create_resource(ResourceType::Stone, default_collect_resource());
+        let worker_id = default_worker(&mut v);
+        v.assign_worker(worker_id, stone_id).unwrap();
+
+        let shock = v.start_shock(ShockKind::MineCollapse, 2).unwrap();
+        assert_eq!(Some(stone_id), shock.resource_id);
+        assert!(!v.is_collapsed(food_id));
+        assert!(v.start_shock(ShockKind::MineCollapse, 2).is_err());
+
+        v.simulate();
+        assert_eq!(0.0, *v.stockpile.get(&ResourceType::Stone).unwrap());
+        v.simulate();
+        assert_eq!(0.0, *v.stockpile.get(&ResourceType::Stone).unwrap());
+        v.simulate();
+        assert!(*v.stockpile.get(&ResourceType::Stone).unwrap() > 0.0);
+    }
+
+    #[test]
+    fn plague_kills_workers() {
+        let mut v = default_village();
+        default_worker(&mut v);
+        v.start_shock(ShockKind::Plague { hazard: 1.0 }, 3).unwrap();
+
+        v.simulate();
+
+        assert_eq!(1, v.deaths(DeathCause::Plague));
+    }
+
+    #[test]
+    fn gold_discovery() {
+        let mut v = default_village();
+
+        let shock = v.start_shock(ShockKind::GoldDiscovery { amount: 25.0 }, 5).unwrap();
+
+        assert_eq!(0, shock.remaining);
+        assert_eq!(0, v.shocks().len());
+        assert_eq!(25.0, *v.stockpile.get(&ResourceType::Gold).unwrap());
+    }
+
+    #[test]
+    fn no_harvest_in_winter() {
+        let mut v = default_village();
+        let calendar = Calendar::temperate();
+        let resource_id = v.create_resource(ResourceType::Food, default_collect_resource());
+        let worker_id = default_worker(&mut v);
+        v.assign_worker(worker_id, resource_id).unwrap();
+
+        v.set_date(Date { tick: 100, year: 1, season: Season::Winter, day: 10 }, &calendar);
+        v.simulate();
+
+        assert_eq!(Season::Winter, v.date().season);
+        assert_eq!(0.0, *v.stockpile.get(&ResourceType::Food).unwrap());
+        assert_eq!(1.0, v.production_multiplier(ResourceType::Stone));
+    }
+
+    #[test]
+    fn take_events() {
+        let mut v = default_village();
+        let resource_id = v.create_resource(ResourceType::Food, default_collect_resource());
+        let worker_id = default_worker(&mut v);
+        v.assign_worker(worker_id, resource_id).unwrap();
+        v.simulate();
+
+        assert_eq!(vec!(
+            VillageEvent::WorkerBorn { worker_id: worker_id },
+            VillageEvent::WorkerReassigned { worker_id: worker_id, task: Task::Resource, resource_id: resource_id },
+            VillageEvent::ResourceCollected { resource_id: resource_id, resource_type: ResourceType::Food, amount: 1.0 },
+        ), v.take_events());
+        assert_eq!(0, v.take_events().len());
+    }
+
+    #[test]
+    fn simulate_pay_wages() {
+        let mut v = default_village();
+        default_worker(&mut v);
+        default_worker(&mut v);
+        v.wage = 2.0;
+        *v.stockpile.get_mut(&ResourceType::Gold).unwrap() = 10.0;
+
+        v.simulate();
+
+        assert_eq!(6, *v.stockpile.get(&ResourceType::Gold).unwrap() as u32);
+        assert_eq!(2.0, v.paid_wage());
+        assert_eq!(4.0, v.household_savings());
+    }
+
+    #[test]
+    fn simulate_dead_worker_savings_return_to_village() {
+        let mut v = Village::new(|w| w.age > 1);
+        default_worker(&mut v);
+        v.wage = 2.0;
+        *v.stockpile.get_mut(&ResourceType::Gold).unwrap() = 10.0;
+
+        v.simulate();
+        v.simulate();
+
+        assert_eq!(0, v.workers().len());
+        assert_eq!(0.0, v.household_savings());
+        assert_eq!(10.0, *v.stockpile.get(&ResourceType::Gold).unwrap());
+    }
+
+    #[test]
+    fn simulate_pay_wages_short_of_gold() {
+        let mut v = default_village();
+        default_worker(&mut v);
+        default_worker(&mut v);
+        v.wage = 2.0;
+        *v.stockpile.get_mut(&ResourceType::Gold).unwrap() = 1.0;
+
+        v.simulate();
+
+        assert_eq!(0, *v.stockpile.get(&ResourceType::Gold).unwrap() as u32);
+        assert_eq!(0.5, v.paid_wage());
+    }
+
+    #[test]
+    fn remove_and_adopt_worker() {
+        let mut v1 = default_village();
+        let mut v2 = default_village();
+        let r1 = v1.create_resource(ResourceType::Wood, default_collect_resource());
+        let w1 = default_worker(&mut v1);
+        default_worker(&mut v2);
+        v1.assign_worker(w1, r1).unwrap();
+
+        let worker = v1.remove_worker(w1).unwrap();
+        let new_id = v2.adopt_worker(worker);
+
+        assert!(v1.worker(w1).is_none());
+        assert_eq!(2, new_id);
+        assert_eq!(2, v2.idle_worker_count());
+        assert!(v1.remove_worker(w1).is_none());
+    }
+
+    #[test]
+    fn simulate_food_spoils() {
+        let mut v = default_village();
+        *v.stockpile.get_mut(&ResourceType::Food).unwrap() = 10.0;
+        *v.stockpile.get_mut(&ResourceType::Stone).unwrap() = 10.0;
+
+        v.simulate();
+
+        assert!(*v.stockpile.get(&ResourceType::Food).unwrap() < 10.0);
+        assert!(v.spoiled(ResourceType::Food) > 0.0);
+        assert_eq!(10.0, *v.stockpile.get(&ResourceType::Stone).unwrap());
+        assert_eq!(0.0, v.spoiled(ResourceType::Stone));
+    }
+
+    #[test]
+    fn simulate_storage_overflow() {
+        let mut v = default_village();
+        v.base_storage = 10.0;
+        *v.stockpile.get_mut(&ResourceType::Stone).unwrap() = 15.0;
+        *v.stockpile.get_mut(&ResourceType::Gold).unwrap() = 15.0;
+
+        v.simulate();
+
+        assert_eq!(10, v.stored_goods().round() as u32);
+        assert_eq!(5, v.overflowed(ResourceType::Stone).round() as u32);
+        assert_eq!(15.0, *v.stockpile.get(&ResourceType::Gold).unwrap());
+    }
+
+    #[test]
+    fn warehouses_add_storage() {
+        let mut v = default_village();
+        let base = v.storage_capacity();
+        v.create_building(BuildingType::Warehouse);
+        v.create_building(BuildingType::Warehouse);
+
+        assert_eq!(base + 2.0 * STORAGE_PER_WAREHOUSE, v.storage_capacity());
+    }
+
+    #[test]
+    fn queue_building_pays_cost() {
+        let mut v = default_village();
+        *v.stockpile.get_mut(&ResourceType::Wood).unwrap() = 25.0;
+        *v.stockpile.get_mut(&ResourceType::Stone).unwrap() = 5.0;
+
+        let b1 = v.queue_building(BuildingType::Farm).unwrap();
+
+        assert_eq!(5.0, *v.stockpile.get(&ResourceType::Wood).unwrap());
+        assert_eq!(0.0, *v.stockpile.get(&ResourceType::Stone).unwrap());
+        assert!(!v.building(b1).unwrap().is_complete());
+        assert_eq!(1, v.construction_queue().len());
+    }
+
+    #[test]
+    fn queue_building_not_enough_resources() {
+        let mut v = default_village();
+        *v.stockpile.get_mut(&ResourceType::Wood).unwrap() = 25.0;
+
+        assert!(v.queue_building(BuildingType::Farm).is_err());
+        assert_eq!(25.0, *v.stockpile.get(&ResourceType::Wood).unwrap());
+        assert_eq!(0, v.buildings().len());
+    }
+
+    #[test]
+    fn assign_builder() {
+        let mut v = default_village();
+        let r1 = v.create_resource(ResourceType::Gold, default_collect_resource());
+        let w1 = default_worker(&mut v);
+        v.assign_worker(w1, r1).unwrap();
+
+        v.assign_builder(w1).unwrap();
+
+        assert_eq!(0, v.workers_on_resource(r1).len());
+        assert_eq!(0, v.idle_worker_count());
+        assert_eq!(1, v.builder_count());
+        assert!(v.assign_builder(2).is_err());
+
+        v.assign_worker(w1, 0).unwrap();
+
+        assert_eq!(1, v.idle_worker_count());
+    }
+
+    #[test]
+    fn simulate_builders_complete_queue_in_order() {
+        let mut v = default_village();
+        *v.stockpile.get_mut(&ResourceType::Wood).unwrap() = 50.0;
+        *v.stockpile.get_mut(&ResourceType::Stone).unwrap() = 5.0;
+        *v.stockpile.get_mut(&ResourceType::Gold).unwrap() = 10.0;
+        let b1 = v.queue_building(BuildingType::MarketStall).unwrap();
+        let b2 = v.queue_building(BuildingType::House).unwrap();
+        for _ in 0..3 {
+            let w = default_worker(&mut v);
+            v.assign_builder(w).unwrap();
+        }
+
+        for _ in 0..3 {
+            v.simulate();
+        }
+
+        assert!(!v.building(b1).unwrap().is_complete());
+
+        v.simulate();
+
+        assert!(v.building(b1).unwrap().is_complete());
+        assert_eq!(2, v.building(b2).unwrap().progress);
+    }
+
+    #[test]
+    fn simulate_farm_increases_food() {
+        let mut v = default_village();
+        v.create_resource(ResourceType::Food, |_| 4.0);
+        v.create_building(BuildingType::Farm);
+        *v.stockpile.get_mut(&ResourceType::Wood).unwrap() = 1.0;
+        v.decay_rates.insert(ResourceType::Wood, 0.0);
+
+        v.simulate();
+
+        assert_eq!(5.0, *v.stockpile.get(&ResourceType::Food).unwrap());
+        assert_eq!(0.9, *v.stockpile.get(&ResourceType::Wood).unwrap());
+    }
+
+    #[test]
+    fn simulate_building_inactive_without_upkeep() {
+        let mut v = default_village();
+        v.create_resource(ResourceType::Food, |_| 4.0);
+        let b1 = v.create_building(BuildingType::Farm);
+
+        v.simulate();
+
+        assert!(!v.building(b1).unwrap().is_active);
+        assert_eq!(4.0, *v.stockpile.get(&ResourceType::Food).unwrap());
+    }
+
+    #[test]
+    fn simulate_market_stall_gold() {
+        let mut v = default_village();
+        v.create_building(BuildingType::MarketStall);
+
+        v.simulate();
+
+        assert_eq!(MARKET_STALL_GOLD, *v.stockpile.get(&ResourceType::Gold).unwrap());
+    }
+
+    #[test]
+    fn houses_add_housing() {
+        let mut v = default_village();
+        v.base_housing = 1;
+        default_worker(&mut v);
+
+        assert!(!v.has_free_housing());
+
+        v.create_building(BuildingType::House);
+
+        assert_eq!(1 + WORKERS_PER_HOUSE, v.housing_capacity());
+        assert!(v.has_free_housing());
+    }
+
+    #[test]
+    fn start_research_pays_gold() {
+        let mut v = default_village();
+        *v.stockpile.get_mut(&ResourceType::Gold).unwrap() = 25.0;
+
+        v.start_research(Technology::CropRotation).unwrap();
+
+        assert_eq!(5.0, *v.stockpile.get(&ResourceType::Gold).unwrap());
+        assert_eq!(Technology::CropRotation, v.research().unwrap().technology);
+        assert!(v.start_research(Technology::Forestry).is_err());
+    }
+
+    #[test]
+    fn start_research_needs_prerequisites_and_gold() {
+        let mut v = default_village();
+
+        assert!(v.start_research(Technology::CropRotation).is_err());
+
+        *v.stockpile.get_mut(&ResourceType::Gold).unwrap() = 100.0;
+
+        assert!(v.start_research(Technology::Mining).is_err());
+        assert!(v.research().is_none());
+    }
+
+    #[test]
+    fn simulate_research_unlocks_technology() {
+        let mut v = default_village();
+        *v.stockpile.get_mut(&ResourceType::Gold).unwrap() = 20.0;
+        v.start_research(Technology::Forestry).unwrap();
+        for _ in 0..10 {
+            let w = default_worker(&mut v);
+            v.assign_researcher(w).unwrap();
+        }
+
+        assert_eq!(0, v.idle_worker_count());
+        assert_eq!(10, v.researcher_count());
+
+        v.simulate();
+        v.simulate();
+
+        assert_eq!(20, v.research().unwrap().progress);
+
+        v.simulate();
+
+        assert!(v.research().is_none());
+        assert!(v.has_technology(Technology::Forestry));
+        assert!(v.start_research(Technology::Forestry).is_err());
+    }
+
+    #[test]
+    fn learn_from_partner_speeds_research() {
+        let mut v = default_village();
+        *v.stockpile.get_mut(&ResourceType::Gold).unwrap() = 20.0;
+        v.start_research(Technology::Forestry).unwrap();
+
+        assert!(!v.learn_from_partner(&[Technology::Masonry]));
+        assert!(v.learn_from_partner(&[Technology::Masonry, Technology::Forestry]));
+        assert_eq!(DIFFUSION_PROGRESS, v.research().unwrap().progress);
+    }
+
+    #[test]
+    fn craft_unlocked_recipe() {
+        let mut v = default_village();
+        *v.stockpile.get_mut(&ResourceType::Fish).unwrap() = 5.0;
+        *v.stockpile.get_mut(&ResourceType::Wood).unwrap() = 5.0;
+
+        assert_eq!(Err("Technology not researched"), v.craft(Recipe::SmokedFish, 1));
+
+        v.technologies.push(Technology::Smoking);
+        assert_eq!(Err("Not enough resources"), v.craft(Recipe::SmokedFish, 3));
+        v.craft(Recipe::SmokedFish, 2).unwrap();
+
+        assert_eq!(1.0, *v.stockpile.get(&ResourceType::Fish).unwrap());
+        assert_eq!(4.0, *v.stockpile.get(&ResourceType::Wood).unwrap());
+        assert_eq!(4.0, *v.stockpile.get(&ResourceType::Food).unwrap());
+    }
+
+    #[test]
+    fn technology_increases_production() {
+        let mut v = default_village();
+        v.technologies.push(Technology::Masonry);
+        v.create_resource(ResourceType::Stone, |_| 5.0);
+
+        v.simulate();
+
+        assert_eq!(6.0, *v.stockpile.get(&ResourceType::Stone).unwrap());
+    }
+
+    #[test]
+    fn granaries_reduce_food_need() {
+        let mut v = default_village();
+        let need = v.food_need();
+        v.technologies.push(Technology::Granaries);
+
+        assert!(v.food_need() < need);
+    }
+
+    #[test]
+    fn mine_shaft_needs_mining() {
+        let mut v = default_village();
+        *v.stockpile.get_mut(&ResourceType::Wood).unwrap() = 30.0;
+        *v.stockpile.get_mut(&ResourceType::Stone).unwrap() = 10.0;
+
+        assert!(!v.is_unlocked(BuildingType::MineShaft));
+        assert!(v.queue_building(BuildingType::MineShaft).is_err());
+
+        v.technologies.push(Technology::Mining);
+
+        assert!(v.queue_building(BuildingType::MineShaft).is_ok());
+    }
+
+    #[test]
+    fn simulate_production_tax() {
+        let mut v = default_village();
+        v.tax_policy = TaxPolicy::new(0.25, 0.0, 0.0, false);
+        v.create_resource(ResourceType::Stone, |_| 8.0);
+
+        v.simulate();
+
+        assert_eq!(6.0, *v.stockpile.get(&ResourceType::Stone).unwrap());
+        assert_eq!(2.0, v.treasury.funds(ResourceType::Stone));
+        assert_eq!(2.0, v.treasury.revenue(ResourceType::Stone));
+    }
+
+    #[test]
+    fn simulate_wealth_tax() {
+        let mut v = default_village();
+        v.tax_policy = TaxPolicy::new(0.0, 0.0, 0.1, false);
+        *v.stockpile.get_mut(&ResourceType::Gold).unwrap() = 50.0;
+
+        v.simulate();
+
+        assert_eq!(45.0, *v.stockpile.get(&ResourceType::Gold).unwrap());
+        assert_eq!(5.0, v.treasury.funds(ResourceType::Gold));
+    }
+
+    #[test]
+    fn pay_trade_tax() {
+        let mut v = default_village();
+        v.tax_policy = TaxPolicy::new(0.0, 0.1, 0.0, false);
+        *v.stockpile.get_mut(&ResourceType::Gold).unwrap() = 10.0;
+
+        v.pay_trade_tax(20.0);
+
+        assert_eq!(8.0, *v.stockpile.get(&ResourceType::Gold).unwrap());
+        assert_eq!(2.0, v.treasury.funds(ResourceType::Gold));
+    }
+
+    #[test]
+    fn simulate_food_relief() {
+        let mut v = default_village();
+        v.tax_policy = TaxPolicy::new(0.0, 0.0, 0.0, true);
+        v.treasury.collect(ResourceType::Food, 5.0);
+        *v.stockpile.get_mut(&ResourceType::Wood).unwrap() = 10.0;
+        let w1 = default_worker(&mut v);
+        default_worker(&mut v);
+
+        v.simulate();
+
+        assert_eq!(3.0, v.treasury.funds(ResourceType::Food));
+        assert_eq!(2.0, v.treasury.spending(ResourceType::Food));
+        assert_eq!(1.0, v.food_security());
+        assert!(v.worker(w1).unwrap().health > 0.9);
+    }
+
+    #[test]
+    fn fund_building_from_treasury() {
+        let mut v = default_village();
+        *v.stockpile.get_mut(&ResourceType::Wood).unwrap() = 50.0;
+
+        assert!(v.fund_building(BuildingType::House).is_err());
+
+        v.treasury.collect(ResourceType::Wood, 15.0);
+        v.treasury.collect(ResourceType::Stone, 5.0);
+        let b1 = v.fund_building(BuildingType::House).unwrap();
+
+        assert!(v.building(b1).is_some());
+        assert_eq!(50.0, *v.stockpile.get(&ResourceType::Wood).unwrap());
+        assert_eq!(0.0, v.treasury.funds(ResourceType::Wood));
+        assert_eq!(15.0, v.treasury.spending(ResourceType::Wood));
+    }
+
+    #[test]
+    fn get_resources_by_type() {
+        let mut v = default_village();
+        v.create_resource(ResourceType::Gold, default_collect_resource());
+        v.create_resource(ResourceType::Wood, default_collect_resource());
+        v.create_resource(ResourceType::Wood, default_collect_resource());
+
+        assert_eq!(1, v.resources_of_type(ResourceType::Gold).len());
+        assert_eq!(2, v.resources_of_type(ResourceType::Wood).len());
+        assert_eq!(0, v.resources_of_type(ResourceType::Stone).len());
+        assert_eq!(0, v.resources_of_type(ResourceType::Food).len());
+    }
+
+    #[test]
+    fn receive_inbound_goods() {
+        let mut v = default_village();
+        v.add_inbound(ResourceType::Food, 4.0);
+
+        assert_eq!(4, v.inbound(ResourceType::Food) as u32);
+        assert_eq!(0, *v.stockpile.get(&ResourceType::Food).unwrap() as u32);
+
+        v.receive_inbound(ResourceType::Food, 4.0, false);
+
+        assert_eq!(0, v.inbound(ResourceType::Food) as u32);
+        assert_eq!(4, *v.stockpile.get(&ResourceType::Food).unwrap() as u32);
+    }
+
+    #[test]
+    fn receive_inbound_lost_goods() {
+        let mut v = default_village();
+        v.add_inbound(ResourceType::Food, 4.0);
+        v.receive_inbound(ResourceType::Food, 4.0, true);
+
+        assert_eq!(0, v.inbound(ResourceType::Food) as u32);
+        assert_eq!(0, *v.stockpile.get(&ResourceType::Food).unwrap() as u32);
     }
 
     #[test]
     fn simulate_resource_collect() {
         let mut v = default_village();
-        let r1 = v.create_resource(ResourceType::Wood, |x| 2 as f64);
+        v.create_resource(ResourceType::Wood, |_x| 2.0);
         v.simulate();
 
         assert_eq!(2, *v.stockpile.get(&ResourceType::Wood).unwrap() as u32);
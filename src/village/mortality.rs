@@ -0,0 +1,61 @@
+use village::resource::ResourceType;
+use village::worker::Worker;
+
+#[derive(Copy, Clone, PartialEq, Eq, Hash, Debug)]
+pub enum DeathCause {
+    /// Decided by the Village's CheckForWorkerDeath function
+    Custom,
+    OldAge,
+    Starvation,
+    Accident,
+    /// Caught during a plague
+    Plague,
+}
+
+/// Conditions in the Village a worker is exposed to during a tick
+pub struct MortalityContext {
+    /// Type of the resource the worker is assigned to, None when idle
+    pub resource_type: Option<ResourceType>,
+    /// Food in the stockpile for each living worker
+    pub food_per_worker: f64,
+}
+
+/// Built-in ways for a worker to die, each giving a per tick probability of death
+pub enum MortalityModel {
+    /// Hazard grows exponentially with age: base_hazard * e^(growth_rate * age)
+    Gompertz { base_hazard: f64, growth_rate: f64 },
+    /// Hazard grows with the part of each worker's ration of food the Village cannot cover,
+    /// reaching max_hazard when there is no food at all
+    Starvation { ration: f64, max_hazard: f64 },
+    /// Fixed hazard for workers assigned to resources of the given type (e.g. mining stone)
+    Accident { resource_type: ResourceType, hazard: f64 },
+}
+
+impl MortalityModel {
+    pub fn cause(&self) -> DeathCause {
+        match *self {
+            MortalityModel::Gompertz { .. } => DeathCause::OldAge,
+            MortalityModel::Starvation { .. } => DeathCause::Starvation,
+            MortalityModel::Accident { .. } => DeathCause::Accident,
+        }
+    }
+
+    /// Probability of the worker dying this tick
+    pub fn hazard(&self, worker: &Worker, context: &MortalityContext) -> f64 {
+        match *self {
+            MortalityModel::Gompertz { base_hazard, growth_rate } => {
+                base_hazard * (growth_rate * worker.age as f64).exp()
+            },
+            MortalityModel::Starvation { ration, max_hazard } => {
+                if ration <= 0.0 {
+                    return 0.0;
+                }
+                let shortfall = (1.0 - context.food_per_worker / ration).clamp(0.0, 1.0);
+                max_hazard * shortfall
+            },
+            MortalityModel::Accident { resource_type, hazard } => {
+                if context.resource_type == Some(resource_type) { hazard } else { 0.0 }
+            },
+        }
+    }
+}
@@ -0,0 +1,31 @@
+use std::slice::Iter;
+use village::resource::ResourceType;
+use self::Recipe::*;
+
+/// Conversions of stockpiled goods into other goods, each unlocked by a technology
+#[derive(Copy, Clone, PartialEq, Eq, Hash, Debug)]
+pub enum Recipe {
+    /// Smokes fish over a wood fire into food, which spoils more slowly
+    SmokedFish,
+}
+
+impl Recipe {
+    pub fn iterator() -> Iter<'static, Recipe> {
+        static RECIPES: [Recipe; 1] = [SmokedFish];
+        RECIPES.iter()
+    }
+
+    /// Goods taken from the stockpile for one batch
+    pub fn inputs(&self) -> Vec<(ResourceType, f64)> {
+        match *self {
+            SmokedFish => vec!((ResourceType::Fish, 2.0), (ResourceType::Wood, 0.5)),
+        }
+    }
+
+    /// Goods added to the stockpile by one batch
+    pub fn outputs(&self) -> Vec<(ResourceType, f64)> {
+        match *self {
+            SmokedFish => vec!((ResourceType::Food, 2.0)),
+        }
+    }
+}
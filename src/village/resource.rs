@@ -1,23 +1,49 @@
 use std::slice::Iter;
 use self::ResourceType::*;
 
-#[derive(Copy, Clone, PartialEq, Eq, Hash)]
+#[derive(Copy, Clone, PartialEq, Eq, Hash, Debug)]
 pub enum ResourceType {
     Gold,
     Food,
     Wood,
     Stone,
+    /// Second food good, eaten like food and giving workers a varied diet
+    Fish,
+    /// Currency issued by the monetary authority, traded for gold on the central market
+    Fiat,
 }
 
 impl ResourceType {
     pub fn iterator() -> Iter<'static, ResourceType> {
-        static RESOURCE_TYPES: [ResourceType; 4] = [Gold, Food, Wood, Stone];
-        RESOURCE_TYPES.into_iter()
+        static RESOURCE_TYPES: [ResourceType; 6] = [Gold, Food, Wood, Stone, Fish, Fiat];
+        RESOURCE_TYPES.iter()
+    }
+
+    /// Default share of a stockpiled resource lost each tick
+    pub fn decay_rate(&self) -> f64 {
+        match *self {
+            Gold => 0.0,
+            Food => 0.05,
+            Wood => 0.01,
+            Stone => 0.0,
+            Fish => 0.1,
+            Fiat => 0.0,
+        }
+    }
+
+    /// Whether workers can eat the resource towards their food ration
+    pub fn is_food(&self) -> bool {
+        *self == Food || *self == Fish
+    }
+
+    /// Whether the resource is money rather than a good, so it takes no storage and is left out of price indices
+    pub fn is_money(&self) -> bool {
+        *self == Gold || *self == Fiat
     }
 }
 
 pub type ResourceId = u32;
-pub type CollectResource = fn(worker_power : u32) -> f64;
+pub type CollectResource = fn(worker_power : f64) -> f64;
 
 pub struct Resource {
     pub resource_type : ResourceType,
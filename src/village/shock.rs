@@ -0,0 +1,44 @@
+use village::resource::*;
+
+/// Disaster or windfall that can strike a Village
+#[derive(Copy, Clone, PartialEq, Debug)]
+pub enum ShockKind {
+    /// Cuts the given share of food production
+    Drought { yield_loss: f64 },
+    /// Adds the given probability of death each tick to every worker
+    Plague { hazard: f64 },
+    /// Stops one of the Village's stone or gold resources from producing
+    MineCollapse,
+    /// Adds the given gold to the stockpile when it strikes
+    GoldDiscovery { amount: f64 },
+}
+
+/// Shock affecting a Village
+#[derive(Copy, Clone, PartialEq, Debug)]
+pub struct ActiveShock {
+    pub kind: ShockKind,
+    /// Ticks the shock lasts for
+    pub duration: u32,
+    /// Ticks left before the shock is over
+    pub remaining: u32,
+    /// Resource put out of use by a mine collapse
+    pub resource_id: Option<ResourceId>,
+}
+
+impl ActiveShock {
+    /// Share of production of resources of the given type lost to the shock
+    pub fn yield_loss(&self, resource_type: ResourceType) -> f64 {
+        match self.kind {
+            ShockKind::Drought { yield_loss } if resource_type == ResourceType::Food => yield_loss,
+            _ => 0.0,
+        }
+    }
+
+    /// Probability of death each tick the shock adds to every worker
+    pub fn hazard(&self) -> f64 {
+        match self.kind {
+            ShockKind::Plague { hazard } => hazard,
+            _ => 0.0,
+        }
+    }
+}
@@ -0,0 +1,86 @@
+use village::resource::ResourceType;
+use std::collections::HashMap;
+
+/// Taxes a village government levies, each as a share of the taxed amount
+#[derive(Copy, Clone)]
+pub struct TaxPolicy {
+    /// Share of each tick's production, collected in the goods produced
+    pub production_rate: f64,
+    /// Share of the gold value of each settled trade
+    pub trade_rate: f64,
+    /// Share of the gold on hand, collected every tick
+    pub wealth_rate: f64,
+    /// Release food from the treasury when the stockpile cannot cover the workers' rations
+    pub food_relief: bool,
+}
+
+impl TaxPolicy {
+    pub fn new(production_rate: f64, trade_rate: f64, wealth_rate: f64, food_relief: bool) -> TaxPolicy {
+        TaxPolicy {
+            production_rate: production_rate,
+            trade_rate: trade_rate,
+            wealth_rate: wealth_rate,
+            food_relief: food_relief,
+        }
+    }
+
+    /// No taxes and no relief
+    pub fn none() -> TaxPolicy {
+        TaxPolicy::new(0.0, 0.0, 0.0, false)
+    }
+}
+
+/// Public funds of a village government, kept apart from the village stockpile
+pub struct Treasury {
+    funds: HashMap<ResourceType, f64>,
+    revenue: HashMap<ResourceType, f64>,
+    spending: HashMap<ResourceType, f64>,
+}
+
+impl Default for Treasury {
+    fn default() -> Treasury {
+        Treasury::new()
+    }
+}
+
+impl Treasury {
+    pub fn new() -> Treasury {
+        let mut treasury = Treasury {
+            funds: HashMap::new(),
+            revenue: HashMap::new(),
+            spending: HashMap::new(),
+        };
+
+        for resource_type in ResourceType::iterator() {
+            treasury.funds.insert(*resource_type, 0.0);
+            treasury.revenue.insert(*resource_type, 0.0);
+            treasury.spending.insert(*resource_type, 0.0);
+        }
+
+        treasury
+    }
+
+    pub fn funds(&self, resource_type: ResourceType) -> f64 {
+        *self.funds.get(&resource_type).unwrap()
+    }
+
+    /// Taxes collected over the whole run
+    pub fn revenue(&self, resource_type: ResourceType) -> f64 {
+        *self.revenue.get(&resource_type).unwrap()
+    }
+
+    /// Funds spent over the whole run
+    pub fn spending(&self, resource_type: ResourceType) -> f64 {
+        *self.spending.get(&resource_type).unwrap()
+    }
+
+    pub fn collect(&mut self, resource_type: ResourceType, amount: f64) {
+        *self.funds.get_mut(&resource_type).unwrap() += amount;
+        *self.revenue.get_mut(&resource_type).unwrap() += amount;
+    }
+
+    pub fn spend(&mut self, resource_type: ResourceType, amount: f64) {
+        *self.funds.get_mut(&resource_type).unwrap() -= amount;
+        *self.spending.get_mut(&resource_type).unwrap() += amount;
+    }
+}
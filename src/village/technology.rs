@@ -0,0 +1,109 @@
+use std::slice::Iter;
+use village::resource::ResourceType;
+use village::building::BuildingType;
+use village::recipe::Recipe;
+use self::Technology::*;
+
+/// Technologies change production yields, food upkeep and which building types and recipes can be used
+#[derive(Copy, Clone, PartialEq, Eq, Hash, Debug)]
+pub enum Technology {
+    /// Increases food production
+    CropRotation,
+    /// Increases wood production
+    Forestry,
+    /// Increases stone production
+    Masonry,
+    /// Increases gold production and unlocks mine shafts
+    Mining,
+    /// Lowers the food each worker needs
+    Granaries,
+    /// Unlocks smoking fish into food
+    Smoking,
+}
+
+pub const PRODUCTION_BONUS: f64 = 0.2;
+pub const GRANARIES_RATION_REDUCTION: f64 = 0.2;
+/// Worker-ticks of research gained each tick from trading with a village that already knows the technology
+pub const DIFFUSION_PROGRESS: u32 = 5;
+
+impl Technology {
+    pub fn iterator() -> Iter<'static, Technology> {
+        static TECHNOLOGIES: [Technology; 6] = [CropRotation, Forestry, Masonry, Mining, Granaries, Smoking];
+        TECHNOLOGIES.iter()
+    }
+
+    /// Gold paid when research starts
+    pub fn cost(&self) -> f64 {
+        match *self {
+            CropRotation => 20.0,
+            Forestry => 20.0,
+            Masonry => 30.0,
+            Mining => 50.0,
+            Granaries => 40.0,
+            Smoking => 20.0,
+        }
+    }
+
+    /// Worker-ticks of research needed to unlock the technology
+    pub fn research_time(&self) -> u32 {
+        match *self {
+            CropRotation => 30,
+            Forestry => 30,
+            Masonry => 40,
+            Mining => 60,
+            Granaries => 50,
+            Smoking => 30,
+        }
+    }
+
+    /// Technologies that must be unlocked before research can start
+    pub fn prerequisites(&self) -> Vec<Technology> {
+        match *self {
+            Mining => vec!(Masonry),
+            Granaries => vec!(CropRotation),
+            _ => vec!(),
+        }
+    }
+
+    /// Extra share of production this technology gives resources of the given type
+    pub fn production_bonus(&self, resource_type: ResourceType) -> f64 {
+        match (*self, resource_type) {
+            (CropRotation, ResourceType::Food) |
+            (Forestry, ResourceType::Wood) |
+            (Masonry, ResourceType::Stone) |
+            (Mining, ResourceType::Gold) => PRODUCTION_BONUS,
+            _ => 0.0,
+        }
+    }
+
+    /// Share of each worker's food ration the technology saves
+    pub fn ration_reduction(&self) -> f64 {
+        match *self {
+            Granaries => GRANARIES_RATION_REDUCTION,
+            _ => 0.0,
+        }
+    }
+
+    /// Building types that cannot be built until this technology is unlocked
+    pub fn unlocks(&self) -> Vec<BuildingType> {
+        match *self {
+            Mining => vec!(BuildingType::MineShaft),
+            _ => vec!(),
+        }
+    }
+
+    /// Recipes that cannot be crafted until this technology is unlocked
+    pub fn recipes(&self) -> Vec<Recipe> {
+        match *self {
+            Smoking => vec!(Recipe::SmokedFish),
+            _ => vec!(),
+        }
+    }
+}
+
+/// Research a Village is currently spending worker time on
+pub struct Research {
+    pub technology: Technology,
+    /// Worker-ticks of research done so far
+    pub progress: u32,
+}
@@ -0,0 +1,65 @@
+use village::VillageId;
+use village::resource::ResourceType;
+use std::collections::{HashMap, HashSet};
+
+/// Restrictions a Village puts on trade with each partner, per resource
+#[derive(Clone)]
+pub struct TradePolicy {
+    tariffs: HashMap<(VillageId, ResourceType), f64>,
+    quotas: HashMap<(VillageId, ResourceType), u32>,
+    embargoes: HashSet<(VillageId, ResourceType)>,
+}
+
+impl Default for TradePolicy {
+    fn default() -> TradePolicy {
+        TradePolicy::new()
+    }
+}
+
+impl TradePolicy {
+    pub fn new() -> TradePolicy {
+        TradePolicy {
+            tariffs: HashMap::new(),
+            quotas: HashMap::new(),
+            embargoes: HashSet::new(),
+        }
+    }
+
+    /// Sets the tariff charged on imports of a resource from a partner, as a share of the trade value
+    pub fn set_tariff(&mut self, partner: VillageId, resource_type: ResourceType, rate: f64) {
+        self.tariffs.insert((partner, resource_type), rate);
+    }
+
+    pub fn tariff(&self, partner: VillageId, resource_type: ResourceType) -> f64 {
+        *self.tariffs.get(&(partner, resource_type)).unwrap_or(&0.0)
+    }
+
+    /// Limits the amount of a resource imported from a partner each tick
+    pub fn set_quota(&mut self, partner: VillageId, resource_type: ResourceType, amount: u32) {
+        self.quotas.insert((partner, resource_type), amount);
+    }
+
+    pub fn quota(&self, partner: VillageId, resource_type: ResourceType) -> Option<u32> {
+        self.quotas.get(&(partner, resource_type)).cloned()
+    }
+
+    /// Stops all trade of a resource with a partner, in both directions
+    pub fn embargo(&mut self, partner: VillageId, resource_type: ResourceType) {
+        self.embargoes.insert((partner, resource_type));
+    }
+
+    /// Stops all trade with a partner
+    pub fn embargo_all(&mut self, partner: VillageId) {
+        for resource_type in ResourceType::iterator() {
+            self.embargo(partner, *resource_type);
+        }
+    }
+
+    pub fn lift_embargo(&mut self, partner: VillageId, resource_type: ResourceType) {
+        self.embargoes.remove(&(partner, resource_type));
+    }
+
+    pub fn is_embargoed(&self, partner: VillageId, resource_type: ResourceType) -> bool {
+        self.embargoes.contains(&(partner, resource_type))
+    }
+}
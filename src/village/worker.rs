@@ -1,11 +1,76 @@
+use village::resource::ResourceType;
+use village::VillageId;
+use std::collections::HashMap;
+
 pub type WorkerId = u32;
 
+/// Age (in ticks) at which a worker is at the height of its power
+pub const PRIME_AGE: u32 = 100;
+/// Extra power a worker in its prime has over a newly created worker
+pub const PRIME_BONUS: f64 = 0.5;
+/// Power lost per tick once a worker is past its prime
+pub const AGE_DECLINE_RATE: f64 = 0.005;
+/// Aging never takes a worker below this fraction of its base power
+pub const MIN_AGE_FACTOR: f64 = 0.25;
+
+/// Fraction of the remaining skill gap closed for each tick spent on a resource
+pub const SKILL_GAIN_RATE: f64 = 0.02;
+/// A fully skilled worker produces this much more than an unskilled one
+pub const MAX_SKILL: f64 = 1.0;
+
+/// How quickly health moves towards what the worker's living conditions support
+pub const HEALTH_ADJUST_RATE: f64 = 0.1;
+/// How quickly morale moves towards what the worker's living conditions support
+pub const MORALE_ADJUST_RATE: f64 = 0.2;
+/// Consecutive ticks of work after which a worker counts as overworked
+pub const OVERWORK_STREAK: u32 = 30;
+/// Consecutive ticks of work after which a worker takes a tick off to rest
+pub const REST_STREAK: u32 = 40;
+/// Morale lost while a worker is overworked
+pub const OVERWORK_MORALE_PENALTY: f64 = 0.5;
+/// Share of output a worker keeps with no morale at all
+pub const MIN_MORALE_PRODUCTIVITY: f64 = 0.5;
+/// Share of a well fed worker's health that depends on eating more than one kind of food
+pub const VARIETY_HEALTH_WEIGHT: f64 = 0.1;
+/// Share of a well fed worker's morale that depends on eating more than one kind of food
+pub const VARIETY_MORALE_WEIGHT: f64 = 0.2;
+
+/// What a worker spends its time on
+#[derive(Copy, Clone, PartialEq, Eq, Hash, Debug)]
+pub enum Task {
+    /// Working the assigned resource, or idle when no resource is assigned
+    Resource,
+    /// Working on the Village's construction projects
+    Construction,
+    /// Working on the Village's research
+    Research,
+}
+
+/// Share of each worker's needs the Village covered during a tick, each in the range [0, 1]
+pub struct LivingConditions {
+    /// Share of the food ration on hand, counting every food good
+    pub food: f64,
+    /// How evenly the food on hand is spread over the food goods, 0 for a single food and 1 for equal amounts of each
+    pub variety: f64,
+    /// Share of the heating wood on hand
+    pub heating: f64,
+}
+
 pub struct Worker {
     pub worker_id: WorkerId,
     pub assigned_resource: u32,
+    pub task: Task,
     pub age: u32,
     pub is_alive: bool,
     pub power: u32,
+    pub skills: HashMap<ResourceType, f64>,
+    pub health: f64,
+    pub morale: f64,
+    pub work_streak: u32,
+    /// Gold received as wages and not yet spent
+    pub savings: f64,
+    /// Previous homes of this worker as (village id, worker id in that village), oldest first
+    pub id_history: Vec<(VillageId, WorkerId)>,
 }
 
 impl Worker {
@@ -13,9 +78,74 @@ impl Worker {
         Worker {
             worker_id: worker_id,
             assigned_resource: 0,
+            task: Task::Resource,
             age: 0,
             is_alive: true,
             power: power,
+            skills: HashMap::new(),
+            health: 1.0,
+            morale: 1.0,
+            work_streak: 0,
+            savings: 0.0,
+            id_history: vec!(),
+        }
+    }
+
+    pub fn skill(&self, resource_type: ResourceType) -> f64 {
+        *self.skills.get(&resource_type).unwrap_or(&0.0)
+    }
+
+    /// Improves the worker's skill on a resource type after a tick of work, with diminishing returns
+    pub fn gain_experience(&mut self, resource_type: ResourceType) {
+        let skill = self.skills.entry(resource_type).or_insert(0.0);
+        *skill += (MAX_SKILL - *skill) * SKILL_GAIN_RATE;
+    }
+
+    /// Multiplier on power from age: rises until the prime age and declines afterwards
+    pub fn age_factor(&self) -> f64 {
+        if self.age <= PRIME_AGE {
+            1.0 + PRIME_BONUS * self.age as f64 / PRIME_AGE as f64
+        } else {
+            let decline = AGE_DECLINE_RATE * (self.age - PRIME_AGE) as f64;
+            (1.0 + PRIME_BONUS - decline).max(MIN_AGE_FACTOR)
         }
     }
-}
\ No newline at end of file
+
+    pub fn is_overworked(&self) -> bool {
+        self.work_streak > OVERWORK_STREAK
+    }
+
+    /// A worker that has worked for REST_STREAK ticks in a row does no work this tick, which ends its streak
+    pub fn is_resting(&self) -> bool {
+        self.work_streak >= REST_STREAK
+    }
+
+    /// Moves health and morale towards the levels supported by the given living conditions
+    /// Health follows food, its variety and heating, morale also suffers from overwork
+    pub fn update_wellbeing(&mut self, conditions: &LivingConditions) {
+        // a varied diet only counts for the food actually on hand
+        let varied_food = conditions.food * conditions.variety;
+
+        let health_target = (1.0 - VARIETY_HEALTH_WEIGHT) * (0.7 * conditions.food + 0.3 * conditions.heating)
+            + VARIETY_HEALTH_WEIGHT * varied_food;
+        self.health += (health_target - self.health) * HEALTH_ADJUST_RATE;
+
+        let mut morale_target = (1.0 - VARIETY_MORALE_WEIGHT) * (conditions.food + conditions.heating) / 2.0
+            + VARIETY_MORALE_WEIGHT * varied_food;
+        if self.is_overworked() {
+            morale_target -= OVERWORK_MORALE_PENALTY;
+        }
+        morale_target = morale_target.max(0.0);
+        self.morale += (morale_target - self.morale) * MORALE_ADJUST_RATE;
+    }
+
+    /// Multiplier on output from the worker's health and morale
+    pub fn productivity(&self) -> f64 {
+        self.health * (MIN_MORALE_PRODUCTIVITY + (1.0 - MIN_MORALE_PRODUCTIVITY) * self.morale)
+    }
+
+    /// Power the worker brings to a resource of the given type, adjusted for age, skill, health and morale
+    pub fn effective_power(&self, resource_type: ResourceType) -> f64 {
+        self.power as f64 * self.age_factor() * (1.0 + self.skill(resource_type)) * self.productivity()
+    }
+}
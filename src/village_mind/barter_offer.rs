@@ -0,0 +1,46 @@
+use village::resource::ResourceType;
+use village::VillageId;
+
+/// Direct exchange of goods proposed by one Village to another, outside the central market
+pub struct BarterOffer {
+    /// Village making the offer
+    pub from: VillageId,
+    /// Village the offer is made to
+    pub to: VillageId,
+    /// Goods the offering Village gives
+    pub give: (ResourceType, u32),
+    /// Goods the offering Village wants in return
+    pub take: (ResourceType, u32),
+    /// Round of the negotiation the offer was made in, starting at 1
+    pub round: u32,
+}
+
+impl BarterOffer {
+    pub fn new(to: VillageId, give: (ResourceType, u32), take: (ResourceType, u32)) -> BarterOffer {
+        BarterOffer {
+            from: 0,
+            to: to,
+            give: give,
+            take: take,
+            round: 1,
+        }
+    }
+
+    /// Offer made back to the offering Village with new terms, from the responder's point of view
+    pub fn counter(&self, give: (ResourceType, u32), take: (ResourceType, u32)) -> BarterOffer {
+        BarterOffer {
+            from: self.to,
+            to: self.from,
+            give: give,
+            take: take,
+            round: self.round + 1,
+        }
+    }
+}
+
+pub enum BarterResponse {
+    Accept,
+    /// Propose other terms: goods the responder gives and goods it wants in return
+    Counter { give: (ResourceType, u32), take: (ResourceType, u32) },
+    Reject,
+}
@@ -0,0 +1,25 @@
+use village::resource::ResourceType;
+use village::VillageId;
+
+/// Offer to buy goods from another Village for delivery on a later tick at a price agreed now
+pub struct ForwardRequest {
+    pub seller: VillageId,
+    pub resource_type: ResourceType,
+    pub amount: u32,
+    /// Gold paid for each unit on delivery
+    pub price: u32,
+    /// Ticks from now until delivery
+    pub delivery_in: u32,
+}
+
+impl ForwardRequest {
+    pub fn new(seller: VillageId, resource_type: ResourceType, amount: u32, price: u32, delivery_in: u32) -> ForwardRequest {
+        ForwardRequest {
+            seller: seller,
+            resource_type: resource_type,
+            amount: amount,
+            price: price,
+            delivery_in: delivery_in,
+        }
+    }
+}
@@ -0,0 +1,22 @@
+use village::resource::ResourceType;
+use simulation::credit::Lender;
+
+pub struct LoanRequest {
+    pub lender: Lender,
+    pub amount: f64,
+    /// Ticks over which the loan is repaid
+    pub term: u32,
+    /// Goods pledged to the lender in case of default
+    pub collateral: (ResourceType, f64),
+}
+
+impl LoanRequest {
+    pub fn new(lender: Lender, amount: f64, term: u32, collateral: (ResourceType, f64)) -> LoanRequest {
+        LoanRequest {
+            lender: lender,
+            amount: amount,
+            term: term,
+            collateral: collateral,
+        }
+    }
+}
@@ -1,28 +1,93 @@
 pub mod trade_request;
+pub mod loan_request;
+pub mod barter_offer;
+pub mod forward_request;
+pub mod strategy;
 
 use village::*;
 use village_mind::trade_request::*;
+use village_mind::loan_request::*;
+use village_mind::barter_offer::*;
+use village_mind::forward_request::*;
+use village_mind::strategy::*;
+use village::resource::ResourceType;
+use std::collections::HashMap;
 
 pub struct VillageMind {
     village: VillageRef,
+    strategy: Option<Box<dyn Strategy>>,
 }
 
 impl VillageMind {
     pub fn new(village: VillageRef) -> VillageMind {
         VillageMind {
             village: village,
+            strategy: None,
         }
     }
 
-    /// Decides what to buy and sell
+    /// Hands the mind's decisions over to a strategy
+    pub fn set_strategy(&mut self, strategy: Box<dyn Strategy>) {
+        self.strategy = Some(strategy);
+    }
+
+    /// Decides what to buy and sell at the current prices
     /// Returns a vector of trade requests
-    pub fn trade(&mut self) -> Vec<TradeRequest> {
-        vec!()
+    pub fn trade(&mut self, prices: &HashMap<ResourceType, u32>) -> Vec<TradeRequest> {
+        match self.strategy {
+            Some(ref mut strategy) => strategy.trade(&self.village.borrow(), prices),
+            None => vec!(),
+        }
+    }
+
+    /// Decides which barter offers to make to other villages, used when the market mode is barter
+    /// Returns a vector of barter offers
+    pub fn propose_barters(&mut self) -> Vec<BarterOffer> {
+        match self.strategy {
+            Some(ref mut strategy) => strategy.propose_barters(&self.village.borrow()),
+            None => vec!(),
+        }
+    }
+
+    /// Decides whether to accept, counter or reject a barter offer made to this village
+    pub fn respond_to_barter(&mut self, offer: &BarterOffer) -> BarterResponse {
+        match self.strategy {
+            Some(ref mut strategy) => strategy.respond_to_barter(&self.village.borrow(), offer),
+            None => BarterResponse::Reject,
+        }
+    }
+
+    /// Decides how much gold to borrow and from whom
+    /// Returns a vector of loan requests
+    pub fn request_loans(&mut self) -> Vec<LoanRequest> {
+        match self.strategy {
+            Some(ref mut strategy) => strategy.request_loans(&self.village.borrow()),
+            None => vec!(),
+        }
+    }
+
+    /// Decides which goods to buy ahead for delivery on a later tick, e.g. to hedge against food shortages
+    /// Returns a vector of forward requests
+    pub fn request_forwards(&mut self) -> Vec<ForwardRequest> {
+        match self.strategy {
+            Some(ref mut strategy) => strategy.request_forwards(&self.village.borrow()),
+            None => vec!(),
+        }
+    }
+
+    /// Decides whether to sell goods to the buying Village on the terms of a forward request
+    pub fn accept_forward(&mut self, buyer: VillageId, forward_request: &ForwardRequest) -> bool {
+        match self.strategy {
+            Some(ref mut strategy) => strategy.accept_forward(&self.village.borrow(), buyer, forward_request),
+            None => false,
+        }
     }
 
     /// Manages the village (e.g. prioritizing resources)
     pub fn manage_village(&mut self) {
-
+        if let Some(ref mut strategy) = self.strategy {
+            strategy.manage_village(&mut self.village.borrow_mut());
+        }
     }
 }
 
@@ -33,9 +98,10 @@ mod tests {
     use village_mind::*;
     use std::cell::RefCell;
     use std::rc::Rc;
+    use std::collections::HashMap;
 
     fn default_village_ref() -> Rc<RefCell<Village>> {
-        Rc::new(RefCell::new(Village::new(|w: &Worker| false)))
+        Rc::new(RefCell::new(Village::new(|_w: &Worker| false)))
     }
 
     #[test]
@@ -43,6 +109,6 @@ mod tests {
         let v1 = default_village_ref();
         let mut mind1 = VillageMind::new(v1);
 
-        assert_eq!(0, mind1.trade().len());
+        assert_eq!(0, mind1.trade(&HashMap::new()).len());
     }
 }
\ No newline at end of file
@@ -0,0 +1,42 @@
+use village::*;
+use village::resource::ResourceType;
+use village_mind::trade_request::*;
+use village_mind::loan_request::*;
+use village_mind::barter_offer::*;
+use village_mind::forward_request::*;
+use std::collections::HashMap;
+
+/// Decisions a VillageMind can hand over to code outside the engine, e.g. a strategy written in Python
+/// Every decision defaults to doing nothing
+pub trait Strategy {
+    /// Decides what to buy and sell at the current prices
+    /// In the central market this is called once per clearing round, with the prices of that round, until every price
+    /// settles or `Simulation::max_clearing_rounds` is reached. The amounts asked for are for the whole tick: what was
+    /// already traded this tick is taken off a request returned again, so asking for 5 food every round buys at most 5
+    fn trade(&mut self, _village: &Village, _prices: &HashMap<ResourceType, u32>) -> Vec<TradeRequest> {
+        vec!()
+    }
+
+    fn propose_barters(&mut self, _village: &Village) -> Vec<BarterOffer> {
+        vec!()
+    }
+
+    fn respond_to_barter(&mut self, _village: &Village, _offer: &BarterOffer) -> BarterResponse {
+        BarterResponse::Reject
+    }
+
+    fn request_loans(&mut self, _village: &Village) -> Vec<LoanRequest> {
+        vec!()
+    }
+
+    fn request_forwards(&mut self, _village: &Village) -> Vec<ForwardRequest> {
+        vec!()
+    }
+
+    fn accept_forward(&mut self, _village: &Village, _buyer: VillageId, _forward_request: &ForwardRequest) -> bool {
+        false
+    }
+
+    /// Manages the village (e.g. assigning workers to resources)
+    fn manage_village(&mut self, _village: &mut Village) {}
+}
@@ -1,16 +1,19 @@
 use village::resource::ResourceType;
+use village::VillageId;
 
-#[derive(Copy, Clone, PartialEq, Eq, Hash)]
+#[derive(Copy, Clone, PartialEq, Eq, Hash, Debug)]
 pub enum TradeType {
     Buy,
     Sell,
 }
 
+#[derive(Clone)]
 pub struct TradeRequest {
     pub trade_type : TradeType,
     pub resource_type : ResourceType,
     pub request_amount : u32,
     pub fulfilled_amount : u32,
+    pub village_id : VillageId,
 }
 
 impl TradeRequest {
@@ -20,6 +23,7 @@ impl TradeRequest {
             request_amount: amount,
             resource_type: resource_type,
             fulfilled_amount: 0,
+            village_id: 0,
         }
     }
 }
\ No newline at end of file
@@ -0,0 +1,141 @@
+use simulation::Simulation;
+use scenario::json::*;
+use village::*;
+use serde_json::Value;
+use wasm_bindgen::prelude::*;
+
+fn parse_json(text: &str) -> Result<Value, JsError> {
+    serde_json::from_str(text).map_err(|_| JsError::new("Invalid JSON"))
+}
+
+/// A Simulation driven from JavaScript, taking and returning JSON in the same shape as the server API
+#[wasm_bindgen(js_name = Simulation)]
+pub struct WasmSimulation {
+    simulation: Simulation,
+}
+
+#[wasm_bindgen(js_class = Simulation)]
+impl WasmSimulation {
+    /// Builds the Simulation of a JSON scenario, e.g. {"seed": 1, "villages": [{"resources": ["Food"], "workers": [{"power": 1, "resource": 0}]}]}
+    #[wasm_bindgen(constructor)]
+    pub fn new(scenario: &str) -> Result<WasmSimulation, JsError> {
+        let scenario = parse_scenario(&parse_json(scenario)?).map_err(JsError::new)?;
+        Ok(WasmSimulation {
+            simulation: scenario.build(),
+        })
+    }
+
+    pub fn step(&mut self, ticks: u32) {
+        for _ in 0..ticks {
+            self.simulation.simulate();
+        }
+    }
+
+    #[wasm_bindgen(getter)]
+    pub fn tick(&self) -> u32 {
+        self.simulation.tick
+    }
+
+    /// Price and direction of each resource as JSON
+    pub fn prices(&self) -> String {
+        prices_json(&self.simulation).to_string()
+    }
+
+    /// Population, idle workers and stockpile of each Village as JSON
+    pub fn villages(&self) -> String {
+        Value::Array(self.simulation.village_managers().iter()
+            .map(|vm| village_json(vm.village_id, &vm.village.borrow()))
+            .collect())
+            .to_string()
+    }
+
+    /// Stockpile of a Village as JSON
+    pub fn stockpile(&self, village_id: VillageId) -> Result<String, JsError> {
+        let village = self.simulation.village(village_id).ok_or_else(|| JsError::new("Invalid Village ID"))?;
+        Ok(goods_json(&village.borrow().stockpile).to_string())
+    }
+
+    /// Submits a JSON trade, e.g. {"trade_type": "Buy", "resource": "Food", "amount": 5}, cleared in the next tick
+    pub fn submit_trade(&mut self, village_id: VillageId, trade: &str) -> Result<(), JsError> {
+        let trade_request = parse_trade_request(&parse_json(trade)?).map_err(JsError::new)?;
+        self.simulation.submit_trade(village_id, trade_request).map_err(JsError::new)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use wasm::*;
+    use village::resource::ResourceType;
+    #[cfg(target_arch = "wasm32")]
+    use wasm_bindgen_test::wasm_bindgen_test;
+
+    const SCENARIO: &str = r#"{"seed": 1, "villages": [
+        {"resources": ["Food"], "workers": [{"power": 2, "resource": 0}], "stockpile": {"Gold": 30}}
+    ]}"#;
+
+    #[cfg_attr(target_arch = "wasm32", wasm_bindgen_test)]
+    #[cfg_attr(not(target_arch = "wasm32"), test)]
+    fn step_and_read_json() {
+        let mut simulation = WasmSimulation::new(SCENARIO).unwrap_or_else(|_| panic!("invalid scenario"));
+        simulation.step(3);
+        assert_eq!(3, simulation.tick());
+
+        let prices: Value = serde_json::from_str(&simulation.prices()).unwrap();
+        assert!(prices["Food"]["price"].is_u64());
+
+        let villages: Value = serde_json::from_str(&simulation.villages()).unwrap();
+        assert_eq!(1, villages.as_array().unwrap().len());
+
+        let stockpile: Value = serde_json::from_str(&simulation.stockpile(1).unwrap_or_else(|_| panic!("invalid village"))).unwrap();
+        assert_eq!(30.0, stockpile["Gold"].as_f64().unwrap());
+        assert!(stockpile["Food"].as_f64().unwrap() > 0.0);
+    }
+
+    #[cfg_attr(target_arch = "wasm32", wasm_bindgen_test)]
+    #[cfg_attr(not(target_arch = "wasm32"), test)]
+    fn submit_trade_json() {
+        let mut simulation = WasmSimulation::new(SCENARIO).unwrap_or_else(|_| panic!("invalid scenario"));
+
+        assert!(simulation.submit_trade(1, r#"{"trade_type": "Buy", "resource": "Food", "amount": 5}"#).is_ok());
+    }
+
+    #[cfg_attr(target_arch = "wasm32", wasm_bindgen_test)]
+    #[cfg_attr(not(target_arch = "wasm32"), test)]
+    fn submitted_trade_cleared_next_tick() {
+        let mut simulation = WasmSimulation::new(SCENARIO).unwrap_or_else(|_| panic!("invalid scenario"));
+        simulation.submit_trade(1, r#"{"trade_type": "Buy", "resource": "Food", "amount": 5}"#).unwrap_or_else(|_| panic!("invalid trade"));
+
+        simulation.step(1);
+
+        let rounds = simulation.simulation.clearing_rounds();
+        assert_eq!(5, rounds[0].buys);
+        assert_eq!(ResourceType::Food, rounds[0].resource_type);
+        assert_eq!(1, rounds[0].tick);
+    }
+
+    // JsError can only be created on wasm, so the error paths are only tested there
+    #[cfg(target_arch = "wasm32")]
+    #[wasm_bindgen_test]
+    fn invalid_json_rejected() {
+        assert!(WasmSimulation::new("{").is_err());
+        assert!(WasmSimulation::new(r#"{"villages": [{"resources": ["Iron"]}]}"#).is_err());
+    }
+
+    #[cfg(target_arch = "wasm32")]
+    #[wasm_bindgen_test]
+    fn invalid_village_rejected() {
+        let mut simulation = WasmSimulation::new(SCENARIO).unwrap_or_else(|_| panic!("invalid scenario"));
+
+        assert!(simulation.stockpile(9).is_err());
+        assert!(simulation.submit_trade(9, r#"{"trade_type": "Buy", "resource": "Food", "amount": 5}"#).is_err());
+    }
+
+    #[cfg(target_arch = "wasm32")]
+    #[wasm_bindgen_test]
+    fn invalid_trade_rejected() {
+        let mut simulation = WasmSimulation::new(SCENARIO).unwrap_or_else(|_| panic!("invalid scenario"));
+
+        assert!(simulation.submit_trade(1, r#"{"trade_type": "Lend", "resource": "Food", "amount": 5}"#).is_err());
+        assert!(simulation.submit_trade(1, "not json").is_err());
+    }
+}
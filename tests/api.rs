@@ -0,0 +1,217 @@
+extern crate econ_sim;
+
+use econ_sim::*;
+use std::collections::HashMap;
+
+fn farming_village(seed: u64) -> Village {
+    VillageBuilder::new()
+        .seed(seed)
+        .resource(ResourceType::Food, |x| x * 2.0)
+        .resource(ResourceType::Wood, |x| x)
+        .worker_on(1, 0)
+        .worker_on(1, 0)
+        .worker_on(1, 1)
+        .stockpile(ResourceType::Food, 20.0)
+        .stockpile(ResourceType::Gold, 50.0)
+        .build()
+        .unwrap()
+}
+
+/// Sells some food once and keeps every worker on food
+struct SellFood {
+    sold: bool,
+}
+
+impl Strategy for SellFood {
+    fn trade(&mut self, village: &Village, prices: &HashMap<ResourceType, u32>) -> Vec<TradeRequest> {
+        let food = *village.stockpile.get(&ResourceType::Food).unwrap();
+        if self.sold || food < 10.0 || !prices.contains_key(&ResourceType::Food) {
+            return vec!();
+        }
+        self.sold = true;
+        vec!(TradeRequest::new(TradeType::Sell, 5, ResourceType::Food))
+    }
+
+    fn manage_village(&mut self, village: &mut Village) {
+        let food = village.resources().iter().find(|r| r.resource_type == ResourceType::Food).unwrap().resource_id;
+        let worker_ids: Vec<WorkerId> = village.workers().iter().map(|w| w.worker_id).collect();
+        for worker_id in worker_ids {
+            village.assign_worker(worker_id, food).unwrap();
+        }
+    }
+}
+
+#[test]
+fn build_and_run() {
+    let mut simulation = SimulationBuilder::new()
+        .seed(7)
+        .village(farming_village(1))
+        .village(farming_village(2))
+        .build()
+        .unwrap();
+
+    for _ in 0..10 {
+        simulation.simulate();
+    }
+
+    assert_eq!(10, simulation.tick);
+    assert_eq!(10, simulation.recording().len());
+    let record: &VillageRecord = simulation.recording()[9].village(1).unwrap();
+    assert_eq!(3, record.worker_count);
+    assert!(record.produced.values().any(|amount| *amount > 0.0));
+}
+
+#[test]
+fn policies_from_the_crate_root() {
+    let village = VillageBuilder::new()
+        .resource(ResourceType::Food, |x| x * 10.0)
+        .worker_on(1, 0)
+        .tax_policy(TaxPolicy::new(0.5, 0.0, 0.0, false))
+        .build()
+        .unwrap();
+    let mut simulation = SimulationBuilder::new()
+        .village(village)
+        .shock(ShockRule::new(ShockKind::GoldDiscovery { amount: 5.0 }, 1.0, 1))
+        .monetary_authority(MonetaryAuthority::new(PolicyRule::FixedRate(0.02), 1.0, 1))
+        .build()
+        .unwrap();
+
+    simulation.simulate();
+
+    assert_eq!(1, simulation.shocks().len());
+    assert_eq!(0.02, simulation.credit.central_rate);
+    assert!(simulation.village(1).unwrap().borrow().treasury.revenue(ResourceType::Food) > 0.0);
+}
+
+#[test]
+fn same_seed_same_run() {
+    let run = |seed| {
+        let mut simulation = SimulationBuilder::new().seed(seed).village(farming_village(seed)).build().unwrap();
+        for _ in 0..20 {
+            simulation.simulate();
+        }
+        let village = simulation.village(1).unwrap().borrow();
+        (village.workers().len(), *village.stockpile.get(&ResourceType::Food).unwrap())
+    };
+
+    assert_eq!(run(5), run(5));
+}
+
+#[test]
+fn strategy_minds_village() {
+    let strategy = SellFood { sold: false };
+    let mut simulation = SimulationBuilder::new()
+        .village_with_strategy(farming_village(1), Box::new(strategy))
+        .village(farming_village(2))
+        .build()
+        .unwrap();
+
+    simulation.simulate();
+    simulation.simulate();
+
+    let village = simulation.village(1).unwrap().borrow();
+    let food = village.resources()[0].resource_id;
+    assert!(village.workers().iter().all(|w| w.assigned_resource == food));
+    assert!(simulation.clearing_rounds().iter().any(|r| r.resource_type == ResourceType::Food && r.sells > 0));
+}
+
+/// Asks for the same food every round, whatever the price
+struct AlwaysBuy;
+
+impl Strategy for AlwaysBuy {
+    fn trade(&mut self, _village: &Village, _prices: &HashMap<ResourceType, u32>) -> Vec<TradeRequest> {
+        vec!(TradeRequest::new(TradeType::Buy, 5, ResourceType::Food))
+    }
+}
+
+/// Offers the same food every round and asks for wood nobody sells, so the market keeps clearing
+struct AlwaysSell;
+
+impl Strategy for AlwaysSell {
+    fn trade(&mut self, _village: &Village, _prices: &HashMap<ResourceType, u32>) -> Vec<TradeRequest> {
+        vec!(TradeRequest::new(TradeType::Sell, 15, ResourceType::Food), TradeRequest::new(TradeType::Buy, 1, ResourceType::Wood))
+    }
+}
+
+#[test]
+fn resubmitting_strategy_ends_tick() {
+    use std::cell::RefCell;
+    use std::rc::Rc;
+
+    let mut simulation = SimulationBuilder::new()
+        .max_clearing_rounds(20)
+        .village_with_strategy(farming_village(1), Box::new(AlwaysBuy))
+        .village_with_strategy(farming_village(2), Box::new(AlwaysSell))
+        .build()
+        .unwrap();
+    let bought = Rc::new(RefCell::new(vec!()));
+    let seen = bought.clone();
+    simulation.subscribe(move |e: &Event| match *e {
+        Event::TickStarted { .. } => seen.borrow_mut().push(0),
+        Event::TradeFilled { buyer: 1, amount, .. } => *seen.borrow_mut().last_mut().unwrap() += amount,
+        _ => {},
+    });
+
+    simulation.simulate();
+    simulation.simulate();
+
+    assert_eq!(2, simulation.tick);
+    assert_eq!(20, simulation.clearing_rounds().iter().filter(|r| r.tick == 1 && r.resource_type == ResourceType::Food).count());
+    assert_eq!(vec!(5, 5), *bought.borrow());
+}
+
+#[test]
+fn events_reach_subscribers() {
+    use std::cell::RefCell;
+    use std::rc::Rc;
+
+    let mut simulation = SimulationBuilder::new().village(farming_village(1)).build().unwrap();
+    let ticks = Rc::new(RefCell::new(vec!()));
+    let seen = ticks.clone();
+    simulation.subscribe(move |e: &Event| if let Event::TickStarted { tick } = *e {
+        seen.borrow_mut().push(tick);
+    });
+
+    simulation.simulate();
+    simulation.simulate();
+
+    assert_eq!(vec!(1, 2), *ticks.borrow());
+}
+
+#[test]
+fn errors_convert_with_question_mark() {
+    fn submit(simulation: &mut Simulation, village_id: VillageId) -> Result<()> {
+        simulation.submit_trade(village_id, TradeRequest::new(TradeType::Buy, 5, ResourceType::Food))?;
+        Ok(())
+    }
+
+    let mut simulation = SimulationBuilder::new().village(farming_village(1)).build().unwrap();
+
+    assert!(submit(&mut simulation, 1).is_ok());
+    let error = submit(&mut simulation, 2).unwrap_err();
+    assert_eq!("Invalid Village ID", error.message());
+    let error: Box<dyn std::error::Error> = Box::new(error);
+    assert_eq!("Invalid Village ID", error.to_string());
+}
+
+#[test]
+fn builders_reject_invalid_setups() {
+    assert_eq!(Error::new("Invalid resource index"), VillageBuilder::new().worker_on(1, 0).build().err().unwrap());
+    assert_eq!(Error::new("Invalid barter round limit"), SimulationBuilder::new().max_barter_rounds(0).build().err().unwrap());
+}
+
+#[test]
+fn scenario_builds_same_simulation() {
+    let mut setup = VillageSetup::new();
+    setup.workers = vec!(1, 1);
+    setup.resources = vec!((ResourceType::Food, |x| x));
+    setup.assignments = vec!((0, 0), (1, 0));
+    let mut scenario = Scenario::new(4);
+    scenario.market_mode = MarketMode::Barter;
+    scenario.villages = vec!(setup);
+
+    let simulation = scenario.build();
+
+    assert_eq!(MarketMode::Barter, simulation.market_mode);
+    assert_eq!(0, simulation.village(1).unwrap().borrow().idle_worker_count());
+}